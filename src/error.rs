@@ -14,6 +14,8 @@ pub enum Error {
 pub enum ParseError {
     #[error("{0}")]
     ParseIntError(#[from] std::num::ParseIntError),
+    #[error("expected {expected} components, got {actual}")]
+    WrongDimension { expected: usize, actual: usize },
     #[error("{0}")]
     Other(String),
 }
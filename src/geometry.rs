@@ -0,0 +1,5 @@
+mod aabb;
+mod segment;
+
+pub use aabb::Aabb;
+pub use segment::{Intersection, Segment};
@@ -0,0 +1,178 @@
+use crate::vector::{Coord, Vector};
+
+/// An N-dimensional axis-aligned box of integer points, between the `min` and `max` corners
+/// inclusive. Boxes are never empty.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Aabb<C: Coord, const N: usize> {
+    min: Vector<C, N>,
+    max: Vector<C, N>,
+}
+
+impl<C: Coord, const N: usize> Aabb<C, N> {
+    /// Create a box between `min` and `max` (inclusive), or `None` if `min` is greater than `max`
+    /// on any axis.
+    pub fn new<P: Into<Vector<C, N>>>(min: P, max: P) -> Option<Self> {
+        let (min, max) = (min.into(), max.into());
+        if (0..N).all(|i| min[i] <= max[i]) {
+            Some(Aabb { min, max })
+        } else {
+            None
+        }
+    }
+
+    /// Get the smallest box containing all of `points`, or `None` if there are none.
+    pub fn from_points<I: IntoIterator<Item = Vector<C, N>>>(points: I) -> Option<Self> {
+        points.into_iter().fold(None, |bounds, point| {
+            Some(match bounds {
+                None => Aabb {
+                    min: point,
+                    max: point,
+                },
+                Some(Aabb { min, max }) => Aabb {
+                    min: min.merge_min(&point),
+                    max: max.merge_max(&point),
+                },
+            })
+        })
+    }
+
+    pub fn min(&self) -> Vector<C, N> {
+        self.min
+    }
+
+    pub fn max(&self) -> Vector<C, N> {
+        self.max
+    }
+
+    /// Get the number of points along each axis.
+    pub fn size(&self) -> Vector<C, N> {
+        self.max - self.min + [C::one(); N]
+    }
+
+    pub fn contains<P: Into<Vector<C, N>>>(&self, point: P) -> bool {
+        let point = point.into();
+        (0..N).all(|i| self.min[i] <= point[i] && point[i] <= self.max[i])
+    }
+
+    pub fn contains_box(&self, other: &Self) -> bool {
+        self.contains(other.min) && self.contains(other.max)
+    }
+
+    /// Get the box of points in both boxes, or `None` if they don't overlap.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        Aabb::new(
+            self.min.merge_max(&other.min),
+            self.max.merge_min(&other.max),
+        )
+    }
+
+    /// Get the smallest box containing both boxes.
+    pub fn union(&self, other: &Self) -> Self {
+        Aabb {
+            min: self.min.merge_min(&other.min),
+            max: self.max.merge_max(&other.max),
+        }
+    }
+
+    /// Get the number of points in the box, or `None` if that doesn't fit in a `u128`.
+    pub fn checked_volume(&self) -> Option<u128> {
+        (0..N).try_fold(1u128, |volume, i| {
+            let length = self.max[i].to_i128()? - self.min[i].to_i128()? + 1;
+            volume.checked_mul(length as u128)
+        })
+    }
+
+    /// Get the number of points in the box.
+    ///
+    /// Panics if that doesn't fit in a `u128`.
+    pub fn volume(&self) -> u128 {
+        self.checked_volume().expect("box volume overflowed")
+    }
+
+    /// Get disjoint boxes covering the points of this box that aren't in `other`: at most two
+    /// slabs per axis, either side of the overlap.
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        let overlap = match self.intersection(other) {
+            Some(overlap) => overlap,
+            None => return vec![*self],
+        };
+        let mut pieces = Vec::new();
+        let mut rest = *self;
+        for i in 0..N {
+            if rest.min[i] < overlap.min[i] {
+                let mut below = rest;
+                below.max[i] = overlap.min[i] - C::one();
+                pieces.push(below);
+                rest.min[i] = overlap.min[i];
+            }
+            if rest.max[i] > overlap.max[i] {
+                let mut above = rest;
+                above.min[i] = overlap.max[i] + C::one();
+                pieces.push(above);
+                rest.max[i] = overlap.max[i];
+            }
+        }
+        pieces
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Box3 = Aabb<i64, 3>;
+
+    #[test]
+    fn test_aabb_basics() {
+        assert!(Box3::new([0, 0, 0], [1, -1, 1]).is_none());
+        let a = Box3::new([0, 0, 0], [2, 3, 4]).unwrap();
+        assert_eq!(a.size(), Vector::from([3, 4, 5]));
+        assert_eq!(a.volume(), 60);
+        assert!(a.contains([2, 0, 4]));
+        assert!(!a.contains([2, -1, 4]));
+
+        let b = Box3::new([1, 1, 1], [5, 5, 5]).unwrap();
+        assert_eq!(a.intersection(&b), Box3::new([1, 1, 1], [2, 3, 4]));
+        assert_eq!(a.union(&b), Box3::new([0, 0, 0], [5, 5, 5]).unwrap());
+        assert!(a.union(&b).contains_box(&a));
+        assert!(!a.contains_box(&b));
+        assert_eq!(
+            a.intersection(&Box3::new([3, 0, 0], [3, 0, 0]).unwrap()),
+            None
+        );
+
+        assert_eq!(
+            Box3::from_points([[1, 5, 2], [-1, 0, 7]].map(Vector::from)),
+            Box3::new([-1, 0, 2], [1, 5, 7])
+        );
+        assert_eq!(Box3::from_points([]), None);
+
+        let huge = Aabb::<i64, 3>::new([i64::MIN; 3], [i64::MAX; 3]).unwrap();
+        assert_eq!(huge.checked_volume(), None);
+        let wide = Aabb::<i64, 2>::new([i64::MIN, 0], [i64::MAX, 1]).unwrap();
+        assert_eq!(wide.volume(), 1 << 65);
+    }
+
+    #[test]
+    fn test_aabb_subtract() {
+        let a = Box3::new([0, 0, 0], [9, 9, 9]).unwrap();
+        let b = Box3::new([3, -5, 4], [5, 5, 20]).unwrap();
+        let pieces = a.subtract(&b);
+        let overlap = a.intersection(&b).unwrap();
+        assert_eq!(
+            pieces.iter().map(Aabb::volume).sum::<u128>(),
+            a.volume() - overlap.volume()
+        );
+        for (i, piece) in pieces.iter().enumerate() {
+            assert!(a.contains_box(piece));
+            assert_eq!(piece.intersection(&b), None);
+            for other in pieces[i + 1..].iter() {
+                assert_eq!(piece.intersection(other), None);
+            }
+        }
+
+        assert_eq!(a.subtract(&a), vec![]);
+        let outside = Box3::new([20, 20, 20], [21, 21, 21]).unwrap();
+        assert_eq!(a.subtract(&outside), vec![a]);
+    }
+}
@@ -0,0 +1,271 @@
+use num::{Integer, NumCast, Signed};
+
+use super::Aabb;
+use crate::vector::{Coord, Vector};
+
+/// A straight line segment between two integer points, inclusive of both ends.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Segment<C: Coord, const N: usize> {
+    pub start: Vector<C, N>,
+    pub end: Vector<C, N>,
+}
+
+/// Where two segments meet: a single point, or a shared stretch of collinear segments.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Intersection<C: Coord, const N: usize> {
+    Point(Vector<C, N>),
+    Overlap(Segment<C, N>),
+}
+
+impl<C: Coord + Signed, const N: usize> Segment<C, N> {
+    pub fn new<P: Into<Vector<C, N>>>(start: P, end: P) -> Self {
+        Segment {
+            start: start.into(),
+            end: end.into(),
+        }
+    }
+
+    pub fn direction(&self) -> Vector<C, N> {
+        self.end - self.start
+    }
+
+    /// Get the number of steps between consecutive lattice points along the segment, i.e. the
+    /// gcd of the components of its direction (zero for a single-point segment).
+    fn steps(&self) -> C {
+        let direction = self.direction();
+        (0..N).fold(C::zero(), |gcd, i| gcd.gcd(&direction[i]))
+    }
+
+    /// Get the smallest step between lattice points along the segment, or zero for a
+    /// single-point segment.
+    pub fn step(&self) -> Vector<C, N> {
+        let steps = self.steps();
+        if steps.is_zero() {
+            Vector::default()
+        } else {
+            self.direction() / steps
+        }
+    }
+
+    /// Is the segment parallel to an axis (or a single point)?
+    pub fn is_axis_aligned(&self) -> bool {
+        let direction = self.direction();
+        (0..N).filter(|&i| !direction[i].is_zero()).count() <= 1
+    }
+
+    /// Get the number of integer points on the segment.
+    pub fn point_count(&self) -> usize {
+        self.steps().to_usize().unwrap() + 1
+    }
+
+    /// Iterate over the integer points on the segment, from `start` to `end`.
+    pub fn points(&self) -> impl Iterator<Item = Vector<C, N>> {
+        let step = self.step();
+        std::iter::successors(Some(self.start), move |&point| Some(point + step))
+            .take(self.point_count())
+    }
+
+    pub fn length(&self) -> f64 {
+        self.direction()
+            .squared_euclidean_length()
+            .to_f64()
+            .unwrap()
+            .sqrt()
+    }
+
+    pub fn bounding_box(&self) -> Aabb<C, N> {
+        Aabb::new(
+            self.start.merge_min(&self.end),
+            self.start.merge_max(&self.end),
+        )
+        .unwrap()
+    }
+
+    /// Get `k` such that `point` is `start + k * step()`, if there is one (even outside the
+    /// segment).
+    fn position(&self, point: Vector<C, N>) -> Option<C> {
+        let (step, offset) = (self.step(), point - self.start);
+        let axis = match (0..N).find(|&i| !step[i].is_zero()) {
+            Some(axis) => axis,
+            None => return (offset == Vector::default()).then(C::zero),
+        };
+        let k = offset[axis] / step[axis];
+        (step * k == offset).then_some(k)
+    }
+
+    pub fn contains<P: Into<Vector<C, N>>>(&self, point: P) -> bool {
+        self.position(point.into())
+            .is_some_and(|k| k >= C::zero() && k <= self.steps())
+    }
+
+    /// Get the overlap of two segments lying on the same line, or `None` if they aren't
+    /// collinear or don't meet.
+    fn collinear_overlap(&self, other: &Self) -> Option<Intersection<C, N>> {
+        let (mut lo, mut hi) = (self.position(other.start)?, self.position(other.end)?);
+        if lo > hi {
+            std::mem::swap(&mut lo, &mut hi);
+        }
+        let (lo, hi) = (lo.max(C::zero()), hi.min(self.steps()));
+        let step = self.step();
+        match lo.cmp(&hi) {
+            std::cmp::Ordering::Greater => None,
+            std::cmp::Ordering::Equal => Some(Intersection::Point(self.start + step * lo)),
+            std::cmp::Ordering::Less => Some(Intersection::Overlap(Segment::new(
+                self.start + step * lo,
+                self.start + step * hi,
+            ))),
+        }
+    }
+}
+
+fn cross(a: [i128; 2], b: [i128; 2]) -> i128 {
+    a[0] * b[1] - a[1] * b[0]
+}
+
+impl<C: Coord + Signed + NumCast> Segment<C, 2> {
+    /// Find where two segments meet. Only integer points count, so segments that cross between
+    /// integer points don't intersect.
+    pub fn intersection(&self, other: &Self) -> Option<Intersection<C, 2>> {
+        if self.steps().is_zero() {
+            return other
+                .contains(self.start)
+                .then_some(Intersection::Point(self.start));
+        }
+        // Work in i128, where the cross products can't overflow for coordinates less than 2^62
+        // in magnitude
+        let wide = |v: Vector<C, 2>| [v[0].to_i128().unwrap(), v[1].to_i128().unwrap()];
+        let (r, s) = (wide(self.direction()), wide(other.direction()));
+        let offset = wide(other.start - self.start);
+        let denominator = cross(r, s);
+        if denominator == 0 {
+            // Parallel, and collinear only if the offset between them is parallel too
+            return if cross(offset, r) == 0 {
+                if other.steps().is_zero() {
+                    self.contains(other.start)
+                        .then_some(Intersection::Point(other.start))
+                } else {
+                    self.collinear_overlap(other)
+                }
+            } else {
+                None
+            };
+        }
+        // The lines meet at `self.start + r * t / d` and `other.start + s * u / d`
+        let (mut t, mut u, mut d) = (cross(offset, s), cross(offset, r), denominator);
+        if d < 0 {
+            (t, u, d) = (-t, -u, -d);
+        }
+        if !(0..=d).contains(&t) || !(0..=d).contains(&u) {
+            return None;
+        }
+        // With `t / d` in lowest terms, `r * t / d` is only an integer if `d` divides `r`, and
+        // dividing first keeps the product no larger than `r`
+        let gcd = t.gcd(&d);
+        let (t, d) = (t / gcd, d / gcd);
+        let start = wide(self.start);
+        let mut point = Vector::default();
+        for i in 0..2 {
+            let (quotient, remainder) = r[i].div_rem(&d);
+            if remainder != 0 {
+                return None;
+            }
+            point[i] = C::from(start[i] + quotient * t)?;
+        }
+        Some(Intersection::Point(point))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Segment2 = Segment<i64, 2>;
+
+    #[test]
+    fn test_segment_points() {
+        let segment = Segment2::new([1, 1], [7, 5]);
+        assert_eq!(segment.step(), Vector::from([3, 2]));
+        assert_eq!(
+            segment.points().collect::<Vec<_>>(),
+            vec![
+                Vector::from([1, 1]),
+                Vector::from([4, 3]),
+                Vector::from([7, 5])
+            ]
+        );
+        assert!(segment.contains([4, 3]));
+        assert!(!segment.contains([10, 7]));
+        assert!(!segment.contains([2, 2]));
+        assert!(!segment.is_axis_aligned());
+        assert_eq!(segment.length(), 52f64.sqrt());
+        assert_eq!(segment.bounding_box(), Aabb::new([1, 1], [7, 5]).unwrap());
+
+        let vertical = Segment2::new([3, 4], [3, 1]);
+        assert!(vertical.is_axis_aligned());
+        assert_eq!(vertical.point_count(), 4);
+        assert_eq!(vertical.points().last(), Some(Vector::from([3, 1])));
+
+        let single = Segment2::new([2, 2], [2, 2]);
+        assert_eq!(
+            single.points().collect::<Vec<_>>(),
+            vec![Vector::from([2, 2])]
+        );
+        assert!(single.contains([2, 2]));
+    }
+
+    #[test]
+    fn test_segment_intersection() {
+        let a = Segment2::new([0, 0], [8, 8]);
+        assert_eq!(
+            a.intersection(&Segment2::new([0, 8], [8, 0])),
+            Some(Intersection::Point([4, 4].into()))
+        );
+        // Crossing between integer points
+        assert_eq!(a.intersection(&Segment2::new([0, 1], [1, 0])), None);
+        // The lines cross, but beyond the end of one segment
+        assert_eq!(a.intersection(&Segment2::new([0, 5], [2, 5])), None);
+        assert_eq!(
+            a.intersection(&Segment2::new([5, 0], [5, 5])),
+            Some(Intersection::Point([5, 5].into()))
+        );
+        // Parallel
+        assert_eq!(a.intersection(&Segment2::new([1, 0], [9, 8])), None);
+        // Collinear
+        assert_eq!(
+            a.intersection(&Segment2::new([10, 10], [6, 6])),
+            Some(Intersection::Overlap(Segment2::new([6, 6], [8, 8])))
+        );
+        assert_eq!(
+            a.intersection(&Segment2::new([8, 8], [9, 9])),
+            Some(Intersection::Point([8, 8].into()))
+        );
+        assert_eq!(a.intersection(&Segment2::new([9, 9], [12, 12])), None);
+        // Single points
+        assert_eq!(
+            Segment2::new([3, 3], [3, 3]).intersection(&a),
+            Some(Intersection::Point([3, 3].into()))
+        );
+        assert_eq!(a.intersection(&Segment2::new([3, 4], [3, 4])), None);
+
+        let narrow: Segment<i16, 2> = Segment::new([0, 0], [1000, 1000]);
+        assert_eq!(
+            narrow.intersection(&Segment::new([1000, 0], [0, 1000])),
+            Some(Intersection::Point([500, 500].into()))
+        );
+
+        // Large enough that `r * t` would overflow i128 before dividing
+        let big = 1 << 50;
+        assert_eq!(
+            Segment2::new([0, 0], [big, big]).intersection(&Segment2::new([big, 0], [0, big])),
+            Some(Intersection::Point([big / 2, big / 2].into()))
+        );
+        assert_eq!(
+            Segment2::new([0, 0], [big, big]).intersection(&Segment2::new([1, 0], [1, big])),
+            Some(Intersection::Point([1, 1].into()))
+        );
+        assert_eq!(
+            Segment2::new([0, 0], [big, big - 1]).intersection(&Segment2::new([1, 0], [1, big])),
+            None
+        );
+    }
+}
@@ -0,0 +1,311 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Write};
+use std::io::BufRead;
+use std::str::FromStr;
+
+use crate::error::ParseError;
+
+pub type NodeId = usize;
+
+/// A graph of named nodes, where each name is interned to a `NodeId` so that nodes can be cheaply
+/// compared, copied and used as indexes.
+#[derive(Clone, Debug)]
+pub struct Graph<W = ()> {
+    directed: bool,
+    names: Vec<String>,
+    ids: HashMap<String, NodeId>,
+    edges: Vec<Vec<(NodeId, W)>>,
+}
+
+impl<W> Graph<W> {
+    pub fn directed() -> Self {
+        Graph {
+            directed: true,
+            names: Vec::new(),
+            ids: HashMap::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    pub fn undirected() -> Self {
+        Graph {
+            directed: false,
+            ..Self::directed()
+        }
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// Get the number of nodes.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Get the id of the node called `name`, adding it if it doesn't exist yet.
+    pub fn intern(&mut self, name: &str) -> NodeId {
+        if let Some(&id) = self.ids.get(name) {
+            id
+        } else {
+            let id = self.names.len();
+            self.names.push(name.to_owned());
+            self.ids.insert(name.to_owned(), id);
+            self.edges.push(Vec::new());
+            id
+        }
+    }
+
+    pub fn id(&self, name: &str) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: NodeId) -> &str {
+        self.names[id].as_str()
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> {
+        0..self.len()
+    }
+
+    /// Iterate over the nodes reachable from `id` by a single edge, and the weights of those edges.
+    pub fn neighbours(&self, id: NodeId) -> impl Iterator<Item = (NodeId, &W)> {
+        self.edges[id].iter().map(|(to, weight)| (*to, weight))
+    }
+
+    /// Keep only the edges for which `f(from, to, weight)` is true. In an undirected graph this is
+    /// applied to each direction of each edge separately.
+    pub fn retain_edges<F: FnMut(NodeId, NodeId, &W) -> bool>(&mut self, mut f: F) {
+        for (from, edges) in self.edges.iter_mut().enumerate() {
+            edges.retain(|(to, weight)| f(from, *to, weight));
+        }
+    }
+
+    fn write_dot<F>(&self, path: Option<&[NodeId]>, label: F) -> String
+    where
+        F: Fn(&W) -> Option<String>,
+    {
+        let (keyword, connector) = if self.directed {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+        let path = path.unwrap_or(&[]);
+        let path_nodes: HashSet<NodeId> = path.iter().copied().collect();
+        let path_edges: HashSet<(NodeId, NodeId)> = path
+            .windows(2)
+            .flat_map(|pair| {
+                let (a, b) = (pair[0], pair[1]);
+                if self.directed {
+                    vec![(a, b)]
+                } else {
+                    vec![(a, b), (b, a)]
+                }
+            })
+            .collect();
+
+        let mut output = String::new();
+        writeln!(output, "{} {{", keyword).unwrap();
+        for id in self.nodes() {
+            write!(output, "  {:?}", self.name(id)).unwrap();
+            if path_nodes.contains(&id) {
+                output.push_str(" [color=red]");
+            }
+            output.push_str(";\n");
+        }
+        let mut written: HashSet<(NodeId, NodeId)> = HashSet::new();
+        for from in self.nodes() {
+            for (to, weight) in self.neighbours(from) {
+                // Undirected edges are stored in both directions, but only need writing once
+                if !self.directed && !written.insert((from.min(to), from.max(to))) {
+                    continue;
+                }
+                let mut attributes = Vec::new();
+                if let Some(label) = label(weight) {
+                    attributes.push(format!("label={:?}", label));
+                }
+                if path_edges.contains(&(from, to)) {
+                    attributes.push("color=red".to_owned());
+                }
+                write!(
+                    output,
+                    "  {:?} {} {:?}",
+                    self.name(from),
+                    connector,
+                    self.name(to)
+                )
+                .unwrap();
+                if !attributes.is_empty() {
+                    write!(output, " [{}]", attributes.join(", ")).unwrap();
+                }
+                output.push_str(";\n");
+            }
+        }
+        output.push_str("}\n");
+        output
+    }
+
+    /// Export to Graphviz DOT format, highlighting the nodes and edges of `path` if given.
+    pub fn to_dot(&self, path: Option<&[NodeId]>) -> String {
+        self.write_dot(path, |_| None)
+    }
+}
+
+impl<W: Clone> Graph<W> {
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId, weight: W) {
+        if !self.directed {
+            self.edges[to].push((from, weight.clone()));
+        }
+        self.edges[from].push((to, weight));
+    }
+}
+
+impl<W: fmt::Display> Graph<W> {
+    /// Export to Graphviz DOT format like `to_dot`, labelling each edge with its weight.
+    pub fn to_dot_weighted(&self, path: Option<&[NodeId]>) -> String {
+        self.write_dot(path, |weight| Some(weight.to_string()))
+    }
+}
+
+fn parse_edge(line: &str) -> Result<(&str, &str), ParseError> {
+    line.split_once('-')
+        .ok_or_else(|| ParseError::Other(format!("expected 'a-b', got {:?}", line)))
+}
+
+impl Graph<()> {
+    /// Parse a graph from lines of `a-b` edges.
+    pub fn from_edge_list<R: BufRead>(reader: R, directed: bool) -> crate::Result<Self> {
+        let mut graph = if directed {
+            Graph::directed()
+        } else {
+            Graph::undirected()
+        };
+        for line in reader.lines() {
+            let line = line?;
+            let (a, b) = parse_edge(line.as_str())?;
+            let (a, b) = (graph.intern(a), graph.intern(b));
+            graph.add_edge(a, b, ());
+        }
+        Ok(graph)
+    }
+}
+
+impl<W: Clone + FromStr> Graph<W>
+where
+    ParseError: From<W::Err>,
+{
+    /// Parse a graph from lines of `a-b=weight` edges.
+    pub fn from_weighted_edge_list<R: BufRead>(reader: R, directed: bool) -> crate::Result<Self> {
+        let mut graph = if directed {
+            Graph::directed()
+        } else {
+            Graph::undirected()
+        };
+        for line in reader.lines() {
+            let line = line?;
+            let (edge, weight) = line
+                .split_once('=')
+                .ok_or_else(|| ParseError::Other(format!("expected 'a-b=w', got {:?}", line)))?;
+            let (a, b) = parse_edge(edge)?;
+            let (a, b) = (graph.intern(a), graph.intern(b));
+            graph.add_edge(a, b, weight.parse().map_err(ParseError::from)?);
+        }
+        Ok(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+    use crate::util::read_str;
+
+    #[test]
+    fn test_interning() {
+        let mut graph: Graph = Graph::undirected();
+        let a = graph.intern("a");
+        let b = graph.intern("b");
+        assert_eq!(graph.intern("a"), a);
+        assert_ne!(a, b);
+        assert_eq!(graph.id("b"), Some(b));
+        assert_eq!(graph.id("c"), None);
+        assert_eq!(graph.name(a), "a");
+        assert_eq!(graph.len(), 2);
+    }
+
+    #[test]
+    fn test_undirected() {
+        let graph = Graph::from_edge_list(
+            read_str(indoc! {"\
+                start-A
+                A-b
+                A-end
+                b-end
+            "}),
+            false,
+        )
+        .unwrap();
+        let id = |name| graph.id(name).unwrap();
+        let neighbours = |name| -> Vec<&str> {
+            graph
+                .neighbours(id(name))
+                .map(|(n, _)| graph.name(n))
+                .collect()
+        };
+        assert_eq!(graph.len(), 4);
+        assert_eq!(neighbours("A"), vec!["start", "b", "end"]);
+        assert_eq!(neighbours("end"), vec!["A", "b"]);
+
+        let path = [id("start"), id("A"), id("end")];
+        assert_eq!(
+            graph.to_dot(Some(&path)),
+            indoc! {r#"
+                graph {
+                  "start" [color=red];
+                  "A" [color=red];
+                  "b";
+                  "end" [color=red];
+                  "start" -- "A" [color=red];
+                  "A" -- "b";
+                  "A" -- "end" [color=red];
+                  "b" -- "end";
+                }
+            "#}
+        );
+    }
+
+    #[test]
+    fn test_directed_weighted() {
+        let graph: Graph<u32> = Graph::from_weighted_edge_list(
+            read_str(indoc! {"\
+                a-b=3
+                b-c=4
+            "}),
+            true,
+        )
+        .unwrap();
+        let (a, b) = (graph.id("a").unwrap(), graph.id("b").unwrap());
+        assert_eq!(graph.neighbours(a).collect::<Vec<_>>(), vec![(b, &3)]);
+        assert_eq!(graph.neighbours(b).count(), 1);
+        assert_eq!(
+            graph.to_dot_weighted(None),
+            indoc! {r#"
+                digraph {
+                  "a";
+                  "b";
+                  "c";
+                  "a" -> "b" [label="3"];
+                  "b" -> "c" [label="4"];
+                }
+            "#}
+        );
+
+        assert!(Graph::<u32>::from_weighted_edge_list(read_str("a-b\n"), true).is_err());
+        assert!(Graph::<u32>::from_weighted_edge_list(read_str("a-b=x\n"), true).is_err());
+    }
+}
@@ -2,6 +2,15 @@ use std::ops;
 
 use crate::vector::Vector;
 
+mod automaton;
+mod regions;
+mod sparse;
+mod transform;
+
+pub use automaton::Automaton;
+pub use regions::{Component, Components};
+pub use sparse::SparseGrid;
+
 pub type Point<const N: usize> = Vector<i64, N>;
 
 #[derive(Clone, Copy, Debug)]
@@ -36,6 +45,20 @@ impl<const N: usize> Extent<N> {
         self.0 - [1; N]
     }
 
+    fn clamp(&self, mut point: Point<N>) -> Point<N> {
+        for i in 0..N {
+            point[i] = point[i].clamp(0, self.0[i] - 1);
+        }
+        point
+    }
+
+    fn wrap(&self, mut point: Point<N>) -> Point<N> {
+        for i in 0..N {
+            point[i] = point[i].rem_euclid(self.0[i]);
+        }
+        point
+    }
+
     fn row_major_index(&self, point: Point<N>) -> Option<usize> {
         if self.contains(point) {
             let size = self.0.as_ref();
@@ -72,9 +95,43 @@ impl<const N: usize> Extent<N> {
     }
 }
 
+/// How lookups treat points outside of a grid's extent.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Boundary<T> {
+    /// Points outside of the grid don't exist.
+    Drop,
+    /// Points outside of the grid refer to the nearest point on the edge of the grid.
+    Clamp,
+    /// Points outside of the grid wrap around to the opposite edge, i.e. the grid is toroidal.
+    Wrap,
+    /// The grid is surrounded by an infinite background of this value.
+    Default(T),
+}
+
+#[derive(Clone, Copy)]
+enum Resolve {
+    Drop,
+    Clamp,
+    Wrap,
+    Keep,
+}
+
+impl<T> Boundary<T> {
+    fn resolve_mode(&self) -> Resolve {
+        match self {
+            Boundary::Drop => Resolve::Drop,
+            Boundary::Clamp => Resolve::Clamp,
+            Boundary::Wrap => Resolve::Wrap,
+            Boundary::Default(_) => Resolve::Keep,
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct Grid<T, const N: usize> {
     extent: Extent<N>,
     offset: Point<N>,
+    boundary: Boundary<T>,
     data: Vec<T>,
 }
 
@@ -84,6 +141,7 @@ impl<T, const N: usize> Grid<T, N> {
         Grid {
             extent,
             offset: Vector::default(),
+            boundary: Boundary::Drop,
             data: Vec::with_capacity(extent.volume()),
         }
     }
@@ -93,6 +151,11 @@ impl<T, const N: usize> Grid<T, N> {
         self
     }
 
+    pub fn with_boundary(mut self, boundary: Boundary<T>) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
     pub fn with_data<I: IntoIterator<Item = T>>(mut self, data: I) -> Self {
         self.data.clear();
         self.data.extend(data.into_iter());
@@ -103,15 +166,62 @@ impl<T, const N: usize> Grid<T, N> {
         self.extent.0
     }
 
+    /// Get the number of points in the grid.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
     pub fn offset(&self) -> Point<N> {
         self.offset
     }
 
+    pub fn boundary(&self) -> &Boundary<T> {
+        &self.boundary
+    }
+
+    pub fn set_boundary(&mut self, boundary: Boundary<T>) {
+        self.boundary = boundary;
+    }
+
     pub fn contains<P: Into<Point<N>>>(&self, point: P) -> bool {
         self.extent.contains(point.into() - self.offset)
     }
 
-    fn row_major_index(&self, point: Point<N>) -> Option<usize> {
+    /// Get a function that maps a point to the point it refers to under the boundary policy, or
+    /// `None` if it doesn't exist. Points on a `Boundary::Default` background map to themselves.
+    ///
+    /// The function doesn't borrow the grid, so it can be used while mutating the grid.
+    fn resolver(&self) -> impl Fn(Point<N>) -> Option<Point<N>> + Copy {
+        let extent = self.extent;
+        let offset = self.offset;
+        let mode = self.boundary.resolve_mode();
+        move |point| {
+            let relative = point - offset;
+            if extent.contains(relative) {
+                Some(point)
+            } else {
+                match mode {
+                    Resolve::Drop => None,
+                    Resolve::Clamp => Some(extent.clamp(relative) + offset),
+                    Resolve::Wrap => Some(extent.wrap(relative) + offset),
+                    Resolve::Keep => Some(point),
+                }
+            }
+        }
+    }
+
+    /// Map a point to the point inside the grid that it refers to under the boundary policy.
+    pub fn resolve<P: Into<Point<N>>>(&self, point: P) -> Option<Point<N>> {
+        self.resolver()(point.into()).filter(|p| self.contains(*p))
+    }
+
+    /// Get the position of `point` in the grid's row-major storage order, which is a dense index
+    /// in `0..self.len()`, or `None` if it's outside of the grid.
+    pub fn row_major_index(&self, point: Point<N>) -> Option<usize> {
         self.extent.row_major_index(point - self.offset)
     }
 
@@ -128,14 +238,32 @@ impl<T, const N: usize> Grid<T, N> {
         self.extent.iter_points().map(move |point| point + offset)
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = (Point<N>, &T)> {
+        self.iter_points().zip(self.data.iter())
+    }
+
     pub fn get<P: Into<Point<N>>>(&self, point: P) -> Option<&T> {
-        self.row_major_index(point.into())
-            .and_then(|i| self.data.get(i))
+        // Most lookups are inside the grid, so only apply the boundary policy when they aren't
+        let point = point.into();
+        if let Some(i) = self.row_major_index(point) {
+            return self.data.get(i);
+        }
+        match self.resolve(point) {
+            Some(point) => self.row_major_index(point).and_then(|i| self.data.get(i)),
+            None => match &self.boundary {
+                Boundary::Default(value) => Some(value),
+                _ => None,
+            },
+        }
     }
 
     pub fn get_mut<P: Into<Point<N>>>(&mut self, point: P) -> Option<&mut T> {
-        self.row_major_index(point.into())
-            .and_then(|i| self.data.get_mut(i))
+        let point = point.into();
+        let i = match self.row_major_index(point) {
+            Some(i) => i,
+            None => self.resolve(point).and_then(|p| self.row_major_index(p))?,
+        };
+        self.data.get_mut(i)
     }
 
     pub fn view(&mut self, extent: Point<N>, offset: Point<N>) -> Option<GridView<'_, T, N>> {
@@ -147,13 +275,13 @@ impl<T, P: Into<Point<N>>, const N: usize> ops::Index<P> for Grid<T, N> {
     type Output = T;
 
     fn index(&self, index: P) -> &Self::Output {
-        &self.data[self.extent.row_major_index(index.into()).unwrap()]
+        self.get(index).unwrap()
     }
 }
 
 impl<T, P: Into<Point<N>>, const N: usize> ops::IndexMut<P> for Grid<T, N> {
     fn index_mut(&mut self, index: P) -> &mut Self::Output {
-        &mut self.data[self.extent.row_major_index(index.into()).unwrap()]
+        self.get_mut(index).unwrap()
     }
 }
 
@@ -239,17 +367,28 @@ pub const GRID_DIRECTIONS_8: [[i64; 2]; 8] = [
 ];
 
 impl<T> Grid<T, 2> {
+    /// Render the grid as text, one line per row, using `f` to choose the character for each cell.
+    pub fn render<F: Fn(&T) -> char>(&self, f: F) -> String {
+        let size = self.size();
+        let mut output = String::with_capacity(((size[0] + 1) * size[1]) as usize);
+        for (point, value) in self.iter() {
+            output.push(f(value));
+            if point[0] == self.max_point()[0] {
+                output.push('\n');
+            }
+        }
+        output
+    }
+
     pub fn iter_adjacent_4_points<P: Into<Point<2>>>(
         &self,
         point: P,
     ) -> impl Iterator<Item = Point<2>> {
         let point: Point<2> = point.into();
-        let extent = self.extent;
-        let offset = self.offset;
         GRID_DIRECTIONS_4
             .iter()
             .map(move |direction| point + direction)
-            .filter(move |p| extent.contains(*p - offset))
+            .filter_map(self.resolver())
     }
 
     pub fn iter_adjacent_8_points<P: Into<Point<2>>>(
@@ -257,12 +396,112 @@ impl<T> Grid<T, 2> {
         point: P,
     ) -> impl Iterator<Item = Point<2>> {
         let point: Point<2> = point.into();
-        let extent = self.extent;
-        let offset = self.offset;
         GRID_DIRECTIONS_8
             .iter()
             .map(move |direction| point + direction)
-            .filter(move |p| extent.contains(*p - offset))
+            .filter_map(self.resolver())
+    }
+}
+
+/// A neighbourhood of points surrounding (but not including) a centre point.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Neighbourhood {
+    /// Points within a Manhattan distance of `radius`, e.g. the 2N orthogonal neighbours for a
+    /// radius of 1.
+    VonNeumann(i64),
+    /// Points within a Chebyshev distance of `radius`, e.g. the 3^N - 1 full neighbours for a
+    /// radius of 1.
+    Moore(i64),
+}
+
+impl Neighbourhood {
+    pub const ORTHOGONAL: Neighbourhood = Neighbourhood::VonNeumann(1);
+    pub const FULL: Neighbourhood = Neighbourhood::Moore(1);
+
+    pub fn radius(&self) -> i64 {
+        match *self {
+            Neighbourhood::VonNeumann(radius) | Neighbourhood::Moore(radius) => radius,
+        }
+    }
+
+    /// Get the offsets from a centre point that make up this neighbourhood in N dimensions, which
+    /// are empty for a negative radius.
+    pub fn offsets<const N: usize>(&self) -> Vec<Point<N>> {
+        let radius = self.radius();
+        if radius < 0 {
+            return Vec::new();
+        }
+        let extent = Extent::try_from(Point::from([2 * radius + 1; N])).unwrap();
+        extent
+            .iter_points()
+            .map(|p| p - [radius; N])
+            .filter(|p| *p != Point::default())
+            .filter(|p| match self {
+                Neighbourhood::VonNeumann(_) => p.manhattan_length() <= radius,
+                Neighbourhood::Moore(_) => true,
+            })
+            .collect()
+    }
+}
+
+impl<T, const N: usize> Grid<T, N> {
+    /// Iterate over the points in `neighbourhood` around `point`. This computes the
+    /// neighbourhood's offsets on every call, so hot loops should get them once with
+    /// `Neighbourhood::offsets` and use `iter_offset_points` instead.
+    pub fn iter_neighbour_points<P: Into<Point<N>>>(
+        &self,
+        point: P,
+        neighbourhood: Neighbourhood,
+    ) -> impl Iterator<Item = Point<N>> {
+        let point: Point<N> = point.into();
+        neighbourhood
+            .offsets::<N>()
+            .into_iter()
+            .map(move |direction| point + direction)
+            .filter_map(self.resolver())
+    }
+
+    pub fn iter_neighbours<P: Into<Point<N>>>(
+        &self,
+        point: P,
+        neighbourhood: Neighbourhood,
+    ) -> impl Iterator<Item = (Point<N>, &T)> + '_ {
+        let point: Point<N> = point.into();
+        self.iter_neighbour_points(point, neighbourhood)
+            .filter_map(move |p| self.get(p).map(|value| (p, value)))
+    }
+
+    /// Iterate over the points at each of `offsets` from `point`, as given by
+    /// `Neighbourhood::offsets`.
+    pub fn iter_offset_points<'a, P: Into<Point<N>>>(
+        &self,
+        point: P,
+        offsets: &'a [Point<N>],
+    ) -> impl Iterator<Item = Point<N>> + 'a
+    where
+        T: 'a,
+    {
+        let point: Point<N> = point.into();
+        offsets
+            .iter()
+            .map(move |direction| point + *direction)
+            .filter_map(self.resolver())
+    }
+
+    pub fn iter_offset_neighbours<'a, P: Into<Point<N>>>(
+        &'a self,
+        point: P,
+        offsets: &'a [Point<N>],
+    ) -> impl Iterator<Item = (Point<N>, &'a T)> + 'a {
+        let point: Point<N> = point.into();
+        let resolver = self.resolver();
+        offsets.iter().filter_map(move |direction| {
+            let p = point + *direction;
+            match self.row_major_index(p) {
+                Some(i) => Some((p, &self.data[i])),
+                None => resolver(p).and_then(|p| Some((p, self.get(p)?))),
+            }
+        })
     }
 }
 
@@ -314,4 +553,111 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_neighbourhood_offsets() {
+        assert_eq!(Neighbourhood::ORTHOGONAL.offsets::<2>().len(), 4);
+        assert_eq!(Neighbourhood::FULL.offsets::<2>().len(), 8);
+        assert_eq!(Neighbourhood::ORTHOGONAL.offsets::<3>().len(), 6);
+        assert_eq!(Neighbourhood::FULL.offsets::<3>().len(), 26);
+        assert_eq!(Neighbourhood::FULL.offsets::<4>().len(), 80);
+        assert_eq!(Neighbourhood::VonNeumann(2).offsets::<2>().len(), 12);
+        assert_eq!(Neighbourhood::Moore(2).offsets::<2>().len(), 24);
+        assert_eq!(Neighbourhood::Moore(0).offsets::<3>().len(), 0);
+        assert_eq!(Neighbourhood::Moore(-1).offsets::<2>().len(), 0);
+        assert_eq!(Neighbourhood::VonNeumann(-3).offsets::<3>().len(), 0);
+
+        let mut offsets = Neighbourhood::ORTHOGONAL.offsets::<2>();
+        offsets.sort();
+        assert_eq!(
+            offsets,
+            GRID_DIRECTIONS_4
+                .iter()
+                .map(|&d| Point::from(d))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_grid_neighbours_3d() {
+        let grid: Grid<i64, 3> = Grid::new([3, 3, 3]).with_data(0..27);
+
+        assert_eq!(
+            grid.iter_neighbour_points([1, 1, 1], Neighbourhood::FULL)
+                .count(),
+            26
+        );
+        assert_eq!(
+            grid.iter_neighbour_points([0, 0, 0], Neighbourhood::FULL)
+                .count(),
+            7
+        );
+        assert_eq!(
+            grid.iter_neighbour_points([0, 0, 0], Neighbourhood::ORTHOGONAL)
+                .count(),
+            3
+        );
+
+        let mut values: Vec<i64> = grid
+            .iter_neighbours([1, 1, 0], Neighbourhood::ORTHOGONAL)
+            .map(|(_, &v)| v)
+            .collect();
+        values.sort();
+        assert_eq!(values, vec![1, 3, 5, 7, 13]);
+    }
+
+    #[test]
+    fn test_grid_boundary() {
+        let mut grid: Grid<i64, 2> = Grid::new([3, 2]).with_offset([1, 1]).with_data(0..6);
+        assert_eq!(grid.get([0, 0]), None);
+        assert_eq!(grid[[1, 1]], 0);
+        assert_eq!(grid[[3, 2]], 5);
+        assert_eq!(grid.iter_adjacent_4_points([1, 1]).count(), 2);
+
+        grid.set_boundary(Boundary::Clamp);
+        assert_eq!(grid.get([0, 0]), Some(&0));
+        assert_eq!(grid.get([10, -5]), Some(&2));
+        assert_eq!(grid.resolve([10, 10]), Some(Point::from([3, 2])));
+        assert_eq!(grid.iter_adjacent_4_points([1, 1]).count(), 4);
+
+        grid.set_boundary(Boundary::Wrap);
+        assert_eq!(grid.get([0, 1]), Some(&2));
+        assert_eq!(grid.get([4, 3]), Some(&0));
+        assert_eq!(grid.get([-2, 0]), Some(&3));
+        *grid.get_mut([4, 1]).unwrap() = 10;
+        assert_eq!(grid[[1, 1]], 10);
+        let mut neighbours: Vec<i64> = grid
+            .iter_neighbours([1, 1], Neighbourhood::ORTHOGONAL)
+            .map(|(_, &v)| v)
+            .collect();
+        neighbours.sort();
+        assert_eq!(neighbours, vec![1, 2, 3, 3]);
+
+        grid.set_boundary(Boundary::Default(-1));
+        assert_eq!(grid.get([0, 0]), Some(&-1));
+        assert_eq!(grid.get_mut([0, 0]), None);
+        assert_eq!(grid.resolve([0, 0]), None);
+        let neighbours: Vec<_> = grid.iter_neighbours([1, 1], Neighbourhood::FULL).collect();
+        assert_eq!(neighbours.len(), 8);
+        assert_eq!(neighbours.iter().filter(|(_, &v)| v == -1).count(), 5);
+
+        // The fast path for points inside the grid agrees with resolving every point
+        let offsets = Neighbourhood::FULL.offsets();
+        for boundary in [
+            Boundary::Drop,
+            Boundary::Clamp,
+            Boundary::Wrap,
+            Boundary::Default(-1),
+        ] {
+            grid.set_boundary(boundary);
+            for point in [[1, 1], [2, 1], [3, 2], [0, 0]] {
+                assert_eq!(
+                    grid.iter_offset_neighbours(point, &offsets)
+                        .collect::<Vec<_>>(),
+                    grid.iter_neighbours(point, Neighbourhood::FULL)
+                        .collect::<Vec<_>>()
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,189 @@
+use std::collections::VecDeque;
+
+use super::{Grid, Neighbourhood, Point};
+
+/// A cellular automaton over a grid.
+///
+/// Synchronous steps are double-buffered, so every cell's rule sees the previous generation
+/// regardless of the order cells are updated in. Cascading steps update cells in place, letting a
+/// change in one cell trigger changes in its neighbours.
+pub struct Automaton<T, const N: usize> {
+    current: Grid<T, N>,
+    next: Grid<T, N>,
+    /// The offsets of the neighbourhood, computed once rather than for every cell.
+    offsets: Vec<Point<N>>,
+    generation: usize,
+}
+
+impl<T: Clone + PartialEq, const N: usize> Automaton<T, N> {
+    pub fn new(grid: Grid<T, N>, neighbourhood: Neighbourhood) -> Self {
+        Automaton {
+            next: grid.clone(),
+            current: grid,
+            offsets: neighbourhood.offsets(),
+            generation: 0,
+        }
+    }
+
+    pub fn grid(&self) -> &Grid<T, N> {
+        &self.current
+    }
+
+    pub fn grid_mut(&mut self) -> &mut Grid<T, N> {
+        &mut self.current
+    }
+
+    pub fn into_grid(self) -> Grid<T, N> {
+        self.current
+    }
+
+    /// Get the number of steps that have been applied.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Apply `rule` to every cell at once, based on the cell's point, its value and the values of
+    /// its neighbours in the previous generation. Returns the points of cells that changed.
+    pub fn step<F>(&mut self, mut rule: F) -> Vec<Point<N>>
+    where
+        F: FnMut(Point<N>, &T, &[&T]) -> T,
+    {
+        // The grid may have been replaced through `grid_mut`, so the buffer might not match it
+        if self.next.size() != self.current.size() || self.next.offset() != self.current.offset() {
+            self.next = self.current.clone();
+        }
+        let mut changed = Vec::new();
+        let mut neighbours: Vec<&T> = Vec::new();
+        for (i, (point, value)) in self.current.iter().enumerate() {
+            neighbours.clear();
+            neighbours.extend(
+                self.current
+                    .iter_offset_neighbours(point, &self.offsets)
+                    .map(|(_, v)| v),
+            );
+            let next = rule(point, value, &neighbours);
+            if next != *value {
+                changed.push(point);
+            }
+            self.next.data[i] = next;
+        }
+        self.next.boundary = self.current.boundary.clone();
+        std::mem::swap(&mut self.current, &mut self.next);
+        self.generation += 1;
+        changed
+    }
+
+    /// Apply synchronous steps until a step changes nothing, returning the generation of that
+    /// step, or `None` if it still hasn't stabilised after `limit` steps (e.g. it oscillates).
+    pub fn run_until_stable<F>(&mut self, limit: usize, mut rule: F) -> Option<usize>
+    where
+        F: FnMut(Point<N>, &T, &[&T]) -> T,
+    {
+        for _ in 0..limit {
+            if self.step(&mut rule).is_empty() {
+                return Some(self.generation);
+            }
+        }
+        None
+    }
+
+    /// Apply `update` to every cell in place; any cell for which it returns `true` is triggered,
+    /// and `propagate` is applied to each of its neighbours, which in turn are triggered if that
+    /// returns `true`. Returns the points of triggered cells, in the order they were triggered.
+    pub fn cascade<U, P>(&mut self, mut update: U, mut propagate: P) -> Vec<Point<N>>
+    where
+        U: FnMut(Point<N>, &mut T) -> bool,
+        P: FnMut(Point<N>, &mut T) -> bool,
+    {
+        let mut triggered = Vec::new();
+        let mut queue: VecDeque<Point<N>> = VecDeque::new();
+        for point in self.current.iter_points() {
+            if update(point, &mut self.current[point]) {
+                queue.push_back(point);
+            }
+        }
+        while let Some(point) = queue.pop_front() {
+            triggered.push(point);
+            for adjacent in self.current.iter_offset_points(point, &self.offsets) {
+                if let Some(value) = self.current.get_mut(adjacent) {
+                    if propagate(adjacent, value) {
+                        queue.push_back(adjacent);
+                    }
+                }
+            }
+        }
+        self.generation += 1;
+        triggered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn life(_: Point<2>, &alive: &bool, neighbours: &[&bool]) -> bool {
+        let count = neighbours.iter().filter(|&&&v| v).count();
+        matches!((alive, count), (true, 2) | (_, 3))
+    }
+
+    fn render(automaton: &Automaton<bool, 2>) -> String {
+        automaton
+            .grid()
+            .render(|&alive| if alive { '#' } else { '.' })
+    }
+
+    #[test]
+    fn test_synchronous() {
+        let grid = Grid::new([5, 5]).with_data((0..25).map(|i| [11, 12, 13].contains(&i)));
+        let mut automaton = Automaton::new(grid, Neighbourhood::FULL);
+        assert_eq!(render(&automaton), ".....\n.....\n.###.\n.....\n.....\n");
+
+        let changed = automaton.step(life);
+        assert_eq!(changed.len(), 4);
+        assert_eq!(render(&automaton), ".....\n..#..\n..#..\n..#..\n.....\n");
+
+        automaton.step(life);
+        assert_eq!(render(&automaton), ".....\n.....\n.###.\n.....\n.....\n");
+        assert_eq!(automaton.generation(), 2);
+
+        let grid = Grid::new([4, 4]).with_data((0..16).map(|i| [5, 6, 9].contains(&i)));
+        let mut automaton = Automaton::new(grid, Neighbourhood::FULL);
+        assert_eq!(automaton.run_until_stable(10, life), Some(2));
+        assert_eq!(render(&automaton), "....\n.##.\n.##.\n....\n");
+
+        // A blinker oscillates forever
+        let grid = Grid::new([5, 5]).with_data((0..25).map(|i| [11, 12, 13].contains(&i)));
+        let mut automaton = Automaton::new(grid, Neighbourhood::FULL);
+        assert_eq!(automaton.run_until_stable(10, life), None);
+        assert_eq!(automaton.generation(), 10);
+        assert_eq!(render(&automaton), ".....\n.....\n.###.\n.....\n.....\n");
+    }
+
+    #[test]
+    fn test_replaced_grid() {
+        let mut automaton =
+            Automaton::new(Grid::new([3, 3]).with_data([false; 9]), Neighbourhood::FULL);
+        *automaton.grid_mut() = Grid::new([4, 4])
+            .with_offset([-1, -1])
+            .with_data((0..16).map(|i| [5, 6, 9, 10].contains(&i)));
+        assert!(automaton.step(life).is_empty());
+        assert_eq!(render(&automaton), "....\n.##.\n.##.\n....\n");
+        assert_eq!(automaton.grid().min_point(), Point::from([-1, -1]));
+    }
+
+    #[test]
+    fn test_cascade() {
+        let grid: Grid<u8, 2> = Grid::new([3, 3]).with_data([0, 0, 0, 0, 9, 0, 0, 0, 8]);
+        let mut automaton = Automaton::new(grid, Neighbourhood::FULL);
+        let bump = |_, v: &mut u8| {
+            *v += 1;
+            *v == 10
+        };
+        let triggered = automaton.cascade(bump, bump);
+        assert_eq!(triggered, vec![Point::from([1, 1]), Point::from([2, 2])]);
+        assert_eq!(
+            automaton.grid().iter().map(|(_, &v)| v).collect::<Vec<_>>(),
+            vec![2, 2, 2, 2, 11, 3, 2, 3, 10]
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::VecDeque;
+
+use super::{Grid, Neighbourhood, Point};
+
+/// A connected region of a grid.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Component<const N: usize> {
+    pub size: usize,
+    pub min: Point<N>,
+    pub max: Point<N>,
+}
+
+/// The result of connected-component labelling: a grid of the same shape with the label of the
+/// component each point belongs to (if any), and the components indexed by label.
+pub struct Components<const N: usize> {
+    pub labels: Grid<Option<usize>, N>,
+    pub components: Vec<Component<N>>,
+}
+
+impl<T, const N: usize> Grid<T, N> {
+    /// Flood fill from `seed`, assigning `label` to each point reached, returning the component
+    /// that was filled (or `None` if `seed` isn't an unlabelled passable point).
+    fn fill<F>(
+        &self,
+        seed: Point<N>,
+        offsets: &[Point<N>],
+        passable: &F,
+        labels: &mut [Option<usize>],
+        label: usize,
+    ) -> Option<Component<N>>
+    where
+        F: Fn(Point<N>, &T) -> bool,
+    {
+        let is_candidate = |point: Point<N>, labels: &[Option<usize>]| {
+            self.row_major_index(point)
+                .filter(|&i| labels[i].is_none() && passable(point, &self.data[i]))
+        };
+
+        let i = is_candidate(seed, labels)?;
+        labels[i] = Some(label);
+        let mut component = Component {
+            size: 0,
+            min: seed,
+            max: seed,
+        };
+        let mut next: VecDeque<Point<N>> = VecDeque::new();
+        next.push_back(seed);
+
+        while let Some(point) = next.pop_front() {
+            component.size += 1;
+            component.min = component.min.merge_min(&point);
+            component.max = component.max.merge_max(&point);
+            for adjacent in self.iter_offset_points(point, offsets) {
+                if let Some(i) = is_candidate(adjacent, labels) {
+                    labels[i] = Some(label);
+                    next.push_back(adjacent);
+                }
+            }
+        }
+
+        Some(component)
+    }
+
+    /// Find all points reachable from `seed` by moving through `neighbourhood` between points for
+    /// which `passable` is true.
+    pub fn flood_fill<P, F>(
+        &self,
+        seed: P,
+        neighbourhood: Neighbourhood,
+        passable: F,
+    ) -> Vec<Point<N>>
+    where
+        P: Into<Point<N>>,
+        F: Fn(Point<N>, &T) -> bool,
+    {
+        let mut labels = vec![None; self.data.len()];
+        let offsets = neighbourhood.offsets();
+        self.fill(seed.into(), &offsets, &passable, &mut labels, 0);
+        self.iter_points()
+            .zip(labels)
+            .filter_map(|(point, label)| label.map(|_| point))
+            .collect()
+    }
+
+    /// Label every connected region of points for which `passable` is true, where points are
+    /// connected through `neighbourhood` (e.g. `Neighbourhood::ORTHOGONAL` for 4-connectivity in
+    /// 2D or `Neighbourhood::FULL` for 8-connectivity). Labels are assigned in row-major order.
+    pub fn label_components<F>(&self, neighbourhood: Neighbourhood, passable: F) -> Components<N>
+    where
+        F: Fn(Point<N>, &T) -> bool,
+    {
+        let mut labels = vec![None; self.data.len()];
+        let mut components = Vec::new();
+        let offsets = neighbourhood.offsets();
+        for point in self.iter_points() {
+            let label = components.len();
+            if let Some(component) = self.fill(point, &offsets, &passable, &mut labels, label) {
+                components.push(component);
+            }
+        }
+        Components {
+            labels: Grid::new(self.size())
+                .with_offset(self.offset)
+                .with_data(labels),
+            components,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(lines: &[&str]) -> Grid<bool, 2> {
+        Grid::new([lines[0].len() as i64, lines.len() as i64]).with_data(
+            lines
+                .iter()
+                .flat_map(|line| line.bytes().map(|b| b == b'#')),
+        )
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let grid = parse(&["##..", "#.#.", "..##", "#..#"]);
+        let mut filled = grid.flood_fill([3, 2], Neighbourhood::ORTHOGONAL, |_, &v| v);
+        filled.sort();
+        assert_eq!(
+            filled,
+            vec![
+                Point::from([2, 1]),
+                Point::from([2, 2]),
+                Point::from([3, 2]),
+                Point::from([3, 3]),
+            ]
+        );
+        assert!(grid
+            .flood_fill([2, 0], Neighbourhood::ORTHOGONAL, |_, &v| v)
+            .is_empty());
+        assert_eq!(
+            grid.flood_fill([1, 0], Neighbourhood::FULL, |_, &v| v)
+                .len(),
+            7
+        );
+    }
+
+    #[test]
+    fn test_label_components() {
+        let grid = parse(&["##..", "#.#.", "..##", "#..#"]);
+
+        let result = grid.label_components(Neighbourhood::ORTHOGONAL, |_, &v| v);
+        assert_eq!(result.components.len(), 3);
+        assert_eq!(
+            result.components[0],
+            Component {
+                size: 3,
+                min: [0, 0].into(),
+                max: [1, 1].into(),
+            }
+        );
+        assert_eq!(
+            result.components[1],
+            Component {
+                size: 4,
+                min: [2, 1].into(),
+                max: [3, 3].into(),
+            }
+        );
+        assert_eq!(result.components[2].size, 1);
+        assert_eq!(result.labels[[2, 2]], Some(1));
+        assert_eq!(result.labels[[0, 3]], Some(2));
+        assert_eq!(result.labels[[2, 0]], None);
+
+        let result = grid.label_components(Neighbourhood::FULL, |_, &v| v);
+        assert_eq!(
+            result.components.iter().map(|c| c.size).collect::<Vec<_>>(),
+            vec![7, 1]
+        );
+
+        let result = grid.label_components(Neighbourhood::ORTHOGONAL, |_, &v| !v);
+        assert_eq!(
+            result.components.iter().map(|c| c.size).collect::<Vec<_>>(),
+            vec![3, 5]
+        );
+    }
+}
@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+use std::ops;
+
+use super::{Grid, Neighbourhood, Point};
+
+/// A grid that only stores the points that have been inserted, growing its bounding box to fit.
+#[derive(Clone, Debug)]
+pub struct SparseGrid<T, const N: usize> {
+    data: HashMap<Point<N>, T>,
+    bounds: Option<(Point<N>, Point<N>)>,
+}
+
+impl<T, const N: usize> Default for SparseGrid<T, N> {
+    fn default() -> Self {
+        SparseGrid {
+            data: HashMap::new(),
+            bounds: None,
+        }
+    }
+}
+
+impl<T, const N: usize> SparseGrid<T, N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Get the minimum point of the bounding box, or `None` if the grid is empty.
+    pub fn min_point(&self) -> Option<Point<N>> {
+        self.bounds.map(|(min, _)| min)
+    }
+
+    /// Get the maximum point of the bounding box, or `None` if the grid is empty.
+    pub fn max_point(&self) -> Option<Point<N>> {
+        self.bounds.map(|(_, max)| max)
+    }
+
+    /// Get the size of the bounding box, which is zero in all dimensions if the grid is empty.
+    pub fn size(&self) -> Point<N> {
+        match self.bounds {
+            Some((min, max)) => max - min + [1; N],
+            None => Point::default(),
+        }
+    }
+
+    pub fn contains<P: Into<Point<N>>>(&self, point: P) -> bool {
+        self.data.contains_key(&point.into())
+    }
+
+    pub fn get<P: Into<Point<N>>>(&self, point: P) -> Option<&T> {
+        self.data.get(&point.into())
+    }
+
+    pub fn get_mut<P: Into<Point<N>>>(&mut self, point: P) -> Option<&mut T> {
+        self.data.get_mut(&point.into())
+    }
+
+    pub fn insert<P: Into<Point<N>>>(&mut self, point: P, value: T) -> Option<T> {
+        let point = point.into();
+        self.bounds = Some(match self.bounds {
+            Some((min, max)) => (min.merge_min(&point), max.merge_max(&point)),
+            None => (point, point),
+        });
+        self.data.insert(point, value)
+    }
+
+    pub fn remove<P: Into<Point<N>>>(&mut self, point: P) -> Option<T> {
+        let point = point.into();
+        let removed = self.data.remove(&point);
+        if let (Some(_), Some((min, max))) = (&removed, self.bounds) {
+            // Only need to shrink the bounding box if the point was on its edge
+            if (0..N).any(|i| point[i] == min[i] || point[i] == max[i]) {
+                self.recalculate_bounds();
+            }
+        }
+        removed
+    }
+
+    pub fn retain<F: FnMut(&Point<N>, &mut T) -> bool>(&mut self, f: F) {
+        self.data.retain(f);
+        self.recalculate_bounds();
+    }
+
+    fn recalculate_bounds(&mut self) {
+        self.bounds = self.data.keys().fold(None, |bounds, point| match bounds {
+            Some((min, max)) => Some((point.merge_min(&min), point.merge_max(&max))),
+            None => Some((*point, *point)),
+        });
+    }
+
+    /// Iterate over the points that are present in the grid, in no particular order.
+    pub fn iter_points(&self) -> impl Iterator<Item = Point<N>> + '_ {
+        self.data.keys().copied()
+    }
+
+    /// Iterate over the points and values in the grid, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point<N>, &T)> {
+        self.data.iter().map(|(point, value)| (*point, value))
+    }
+
+    /// Iterate over the points in `neighbourhood` around `point` that are present in the grid.
+    pub fn iter_neighbour_points<P: Into<Point<N>>>(
+        &self,
+        point: P,
+        neighbourhood: Neighbourhood,
+    ) -> impl Iterator<Item = Point<N>> + '_ {
+        self.iter_neighbours(point, neighbourhood).map(|(p, _)| p)
+    }
+
+    pub fn iter_neighbours<P: Into<Point<N>>>(
+        &self,
+        point: P,
+        neighbourhood: Neighbourhood,
+    ) -> impl Iterator<Item = (Point<N>, &T)> + '_ {
+        let point: Point<N> = point.into();
+        neighbourhood
+            .offsets::<N>()
+            .into_iter()
+            .map(move |direction| point + direction)
+            .filter_map(move |p| self.data.get(&p).map(|value| (p, value)))
+    }
+}
+
+impl<T: Clone, const N: usize> SparseGrid<T, N> {
+    /// Convert to a dense grid covering the bounding box, with `fill` for points that are not
+    /// present, or `None` if the grid is empty.
+    pub fn to_grid(&self, fill: T) -> Option<Grid<T, N>> {
+        let (min, max) = self.bounds?;
+        let mut grid = Grid::new(max - min + [1; N]).with_offset(min);
+        let data: Vec<T> = grid
+            .iter_points()
+            .map(|point| self.data.get(&point).unwrap_or(&fill).clone())
+            .collect();
+        grid = grid.with_data(data);
+        Some(grid)
+    }
+}
+
+impl<T> SparseGrid<T, 2> {
+    /// Render the bounding box as text, one line per row, using `f` to choose the character for
+    /// each point (`None` for points that are not present).
+    pub fn render<F: Fn(Option<&T>) -> char>(&self, f: F) -> String {
+        let mut output = String::new();
+        if let Some((min, max)) = self.bounds {
+            for y in min[1]..=max[1] {
+                for x in min[0]..=max[0] {
+                    output.push(f(self.get([x, y])));
+                }
+                output.push('\n');
+            }
+        }
+        output
+    }
+}
+
+impl<T, const N: usize> From<Grid<T, N>> for SparseGrid<T, N> {
+    fn from(grid: Grid<T, N>) -> Self {
+        let points: Vec<Point<N>> = grid.iter_points().collect();
+        points.into_iter().zip(grid.data).collect()
+    }
+}
+
+impl<T, const N: usize> FromIterator<(Point<N>, T)> for SparseGrid<T, N> {
+    fn from_iter<I: IntoIterator<Item = (Point<N>, T)>>(iter: I) -> Self {
+        let mut grid = SparseGrid::new();
+        grid.extend(iter);
+        grid
+    }
+}
+
+impl<T, const N: usize> Extend<(Point<N>, T)> for SparseGrid<T, N> {
+    fn extend<I: IntoIterator<Item = (Point<N>, T)>>(&mut self, iter: I) {
+        for (point, value) in iter {
+            self.insert(point, value);
+        }
+    }
+}
+
+impl<T, P: Into<Point<N>>, const N: usize> ops::Index<P> for SparseGrid<T, N> {
+    type Output = T;
+
+    fn index(&self, index: P) -> &Self::Output {
+        self.get(index).unwrap()
+    }
+}
+
+impl<T, P: Into<Point<N>>, const N: usize> ops::IndexMut<P> for SparseGrid<T, N> {
+    fn index_mut(&mut self, index: P) -> &mut Self::Output {
+        self.get_mut(index).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sparse_grid() {
+        let mut sparse: SparseGrid<u8, 2> = SparseGrid::new();
+        assert!(sparse.is_empty());
+        assert_eq!(sparse.min_point(), None);
+        assert_eq!(sparse.size(), Point::from([0, 0]));
+
+        sparse.insert([2, 3], 1);
+        sparse.insert([-1, 4], 2);
+        sparse.insert([0, 5], 3);
+        assert_eq!(sparse.len(), 3);
+        assert_eq!(sparse.min_point(), Some(Point::from([-1, 3])));
+        assert_eq!(sparse.max_point(), Some(Point::from([2, 5])));
+        assert_eq!(sparse.size(), Point::from([4, 3]));
+        assert_eq!(sparse[[-1, 4]], 2);
+        assert_eq!(sparse.get([0, 0]), None);
+
+        assert_eq!(
+            sparse
+                .iter_neighbour_points([0, 4], Neighbourhood::FULL)
+                .count(),
+            2
+        );
+        assert_eq!(
+            sparse
+                .iter_neighbour_points([0, 4], Neighbourhood::ORTHOGONAL)
+                .count(),
+            2
+        );
+
+        assert_eq!(
+            sparse.render(|v| v.map_or('.', |_| '#')),
+            "...#\n#...\n.#..\n"
+        );
+
+        let grid = sparse.to_grid(0).unwrap();
+        assert_eq!(grid.offset(), Point::from([-1, 3]));
+        assert_eq!(grid.size(), Point::from([4, 3]));
+        assert_eq!(grid[[0, 5]], 3);
+        assert_eq!(grid[[0, 4]], 0);
+        assert_eq!(grid.render(|&v| (b'0' + v) as char), "0001\n2000\n0300\n");
+
+        let round_trip: SparseGrid<u8, 2> = grid.into();
+        assert_eq!(round_trip.len(), 12);
+        assert_eq!(round_trip.min_point(), Some(Point::from([-1, 3])));
+        assert_eq!(round_trip.max_point(), Some(Point::from([2, 5])));
+
+        sparse.remove([2, 3]);
+        assert_eq!(sparse.min_point(), Some(Point::from([-1, 4])));
+        assert_eq!(sparse.max_point(), Some(Point::from([0, 5])));
+    }
+}
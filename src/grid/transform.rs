@@ -0,0 +1,166 @@
+use super::{Extent, Grid, Point};
+
+impl<T: Clone, const N: usize> Grid<T, N> {
+    /// Build a new grid of `size` (with the same offset and boundary), where `source` maps each
+    /// point of the new grid (relative to the offset) to a point of this grid.
+    fn remap<F: Fn(Point<N>) -> Point<N>>(&self, size: Point<N>, source: F) -> Grid<T, N> {
+        let grid = Grid::new(size)
+            .with_offset(self.offset)
+            .with_boundary(self.boundary.clone());
+        let data: Vec<T> = grid
+            .extent
+            .iter_points()
+            .map(|p| {
+                let i = self.extent.row_major_index(source(p)).unwrap();
+                self.data[i].clone()
+            })
+            .collect();
+        grid.with_data(data)
+    }
+
+    /// Crop to the part of the grid between `min` and `max` (inclusive), keeping the same
+    /// coordinates for the points that remain, or `None` if that doesn't overlap the grid.
+    pub fn crop<P: Into<Point<N>>>(&self, min: P, max: P) -> Option<Grid<T, N>> {
+        let min = min.into().merge_max(&self.min_point());
+        let max = max.into().merge_min(&self.max_point());
+        let extent = Extent::try_from(max - min + [1; N]).ok()?;
+        let shift = min - self.offset;
+        let mut grid = self.remap(extent.0, |p| p + shift);
+        grid.offset = min;
+        Some(grid)
+    }
+
+    /// Surround the grid with a border of `width` cells of `value`, keeping the same coordinates
+    /// for the existing points.
+    pub fn pad(&self, width: i64, value: T) -> Grid<T, N> {
+        let grid = Grid::new(self.size() + [2 * width; N])
+            .with_offset(self.offset - [width; N])
+            .with_boundary(self.boundary.clone());
+        let data: Vec<T> = grid
+            .iter_points()
+            .map(|p| {
+                if self.contains(p) {
+                    self[p].clone()
+                } else {
+                    value.clone()
+                }
+            })
+            .collect();
+        grid.with_data(data)
+    }
+}
+
+/// Transforms that treat the grid as an image, i.e. with `x` increasing to the right and `y`
+/// increasing downwards. The resulting grids keep the same offset.
+impl<T: Clone> Grid<T, 2> {
+    pub fn transpose(&self) -> Grid<T, 2> {
+        let size = self.size();
+        self.remap([size[1], size[0]].into(), |p| [p[1], p[0]].into())
+    }
+
+    pub fn rotate_90(&self) -> Grid<T, 2> {
+        let size = self.size();
+        self.remap([size[1], size[0]].into(), |p| {
+            [p[1], size[1] - 1 - p[0]].into()
+        })
+    }
+
+    pub fn rotate_180(&self) -> Grid<T, 2> {
+        let size = self.size();
+        self.remap(size, |p| (size - [1, 1]) - p)
+    }
+
+    pub fn rotate_270(&self) -> Grid<T, 2> {
+        let size = self.size();
+        self.remap([size[1], size[0]].into(), |p| {
+            [size[0] - 1 - p[1], p[0]].into()
+        })
+    }
+
+    pub fn flip_horizontal(&self) -> Grid<T, 2> {
+        let size = self.size();
+        self.remap(size, |p| [size[0] - 1 - p[0], p[1]].into())
+    }
+
+    pub fn flip_vertical(&self) -> Grid<T, 2> {
+        let size = self.size();
+        self.remap(size, |p| [p[0], size[1] - 1 - p[1]].into())
+    }
+
+    /// Repeat the grid `nx` times horizontally and `ny` times vertically, using `f` to derive each
+    /// value from the index of the tile and the original value.
+    pub fn tile<F: Fn(Point<2>, &T) -> T>(&self, nx: i64, ny: i64, f: F) -> Grid<T, 2> {
+        let size = self.size();
+        let grid = Grid::new(size * [nx, ny])
+            .with_offset(self.offset)
+            .with_boundary(self.boundary.clone());
+        let data: Vec<T> = grid
+            .extent
+            .iter_points()
+            .map(|p| {
+                let tile = Point::from([p[0].div_euclid(size[0]), p[1].div_euclid(size[1])]);
+                let source = Point::from([p[0].rem_euclid(size[0]), p[1].rem_euclid(size[1])]);
+                f(
+                    tile,
+                    &self.data[self.extent.row_major_index(source).unwrap()],
+                )
+            })
+            .collect();
+        grid.with_data(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(grid: &Grid<u8, 2>) -> String {
+        grid.render(|&v| v as char)
+    }
+
+    #[test]
+    fn test_grid_transforms() {
+        let grid: Grid<u8, 2> = Grid::new([3, 2]).with_data(b"abcdef".iter().copied());
+        assert_eq!(render(&grid), "abc\ndef\n");
+        assert_eq!(render(&grid.transpose()), "ad\nbe\ncf\n");
+        assert_eq!(render(&grid.rotate_90()), "da\neb\nfc\n");
+        assert_eq!(render(&grid.rotate_180()), "fed\ncba\n");
+        assert_eq!(render(&grid.rotate_270()), "cf\nbe\nad\n");
+        assert_eq!(render(&grid.flip_horizontal()), "cba\nfed\n");
+        assert_eq!(render(&grid.flip_vertical()), "def\nabc\n");
+        assert_eq!(
+            render(&grid.rotate_90().rotate_90().rotate_90().rotate_90()),
+            render(&grid)
+        );
+    }
+
+    #[test]
+    fn test_grid_crop_pad() {
+        let grid: Grid<u8, 2> = Grid::new([3, 2])
+            .with_offset([1, 1])
+            .with_data(b"abcdef".iter().copied());
+
+        let cropped = grid.crop([2, 0], [5, 1]).unwrap();
+        assert_eq!(cropped.min_point(), Point::from([2, 1]));
+        assert_eq!(render(&cropped), "bc\n");
+        assert!(grid.crop([5, 5], [6, 6]).is_none());
+
+        let padded = grid.pad(1, b'.');
+        assert_eq!(padded.min_point(), Point::from([0, 0]));
+        assert_eq!(padded[[1, 1]], b'a');
+        assert_eq!(render(&padded), ".....\n.abc.\n.def.\n.....\n");
+    }
+
+    #[test]
+    fn test_grid_tile() {
+        let grid: Grid<u8, 2> = Grid::new([2, 1]).with_data([0, 1]);
+        let tiled = grid.tile(3, 2, |tile, &v| {
+            v + 10 * tile[0] as u8 + 100 * tile[1] as u8
+        });
+        assert_eq!(tiled.size(), Point::from([6, 2]));
+        assert_eq!(
+            tiled.iter().map(|(_, &v)| v).collect::<Vec<_>>(),
+            vec![0, 1, 10, 11, 20, 21, 100, 101, 110, 111, 120, 121]
+        );
+    }
+}
@@ -1,7 +1,11 @@
 pub mod error;
+pub mod geometry;
+pub mod graph;
 pub mod grid;
 pub mod runner;
+pub mod search;
 pub mod solutions;
+pub mod transform;
 pub mod util;
 pub mod vector;
 
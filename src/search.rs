@@ -0,0 +1,454 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+pub trait Cost: num::Zero + Copy + Ord {}
+impl<T: num::Zero + Copy + Ord> Cost for T {}
+
+/// Rebuild the path to `end` by following predecessors back to the start.
+fn reconstruct_path<S: Clone + Eq + Hash>(predecessors: &HashMap<S, S>, end: &S) -> Vec<S> {
+    let mut path = vec![end.clone()];
+    while let Some(prev) = predecessors.get(path.last().unwrap()) {
+        path.push(prev.clone());
+    }
+    path.reverse();
+    path
+}
+
+/// The result of a search that reached a goal.
+#[derive(Debug)]
+pub struct SearchResult<S, C> {
+    pub goal: S,
+    pub cost: C,
+    predecessors: HashMap<S, S>,
+}
+
+impl<S: Clone + Eq + Hash, C> SearchResult<S, C> {
+    /// Get the path from the start to the goal, including both.
+    pub fn path(&self) -> Vec<S> {
+        reconstruct_path(&self.predecessors, &self.goal)
+    }
+}
+
+/// The shortest distances from a start to every state that was reached.
+#[derive(Debug)]
+pub struct Distances<S, C> {
+    distances: HashMap<S, C>,
+    predecessors: HashMap<S, S>,
+}
+
+impl<S: Clone + Eq + Hash, C: Copy> Distances<S, C> {
+    pub fn get(&self, state: &S) -> Option<C> {
+        self.distances.get(state).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.distances.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.distances.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&S, C)> {
+        self.distances.iter().map(|(state, &cost)| (state, cost))
+    }
+
+    /// Get the path from the start to `state`, including both, if `state` was reached.
+    pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+        if self.distances.contains_key(state) {
+            Some(reconstruct_path(&self.predecessors, state))
+        } else {
+            None
+        }
+    }
+}
+
+/// Breadth-first search from `start` until a state satisfying `is_goal` is found, where every step
+/// costs 1.
+pub fn bfs<S, FN, I, FG>(
+    start: S,
+    mut successors: FN,
+    mut is_goal: FG,
+) -> Option<SearchResult<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    FN: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    FG: FnMut(&S) -> bool,
+{
+    let mut distances: HashMap<S, usize> = HashMap::new();
+    let mut predecessors: HashMap<S, S> = HashMap::new();
+    let mut queue: VecDeque<S> = VecDeque::new();
+    distances.insert(start.clone(), 0);
+    queue.push_back(start);
+
+    while let Some(state) = queue.pop_front() {
+        let cost = distances[&state];
+        if is_goal(&state) {
+            return Some(SearchResult {
+                goal: state,
+                cost,
+                predecessors,
+            });
+        }
+        for next in successors(&state) {
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), cost + 1);
+                predecessors.insert(next.clone(), state.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+/// Breadth-first search from `start`, finding the number of steps to every reachable state.
+pub fn bfs_all<S, FN, I>(start: S, mut successors: FN) -> Distances<S, usize>
+where
+    S: Clone + Eq + Hash,
+    FN: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    let mut distances: HashMap<S, usize> = HashMap::new();
+    let mut predecessors: HashMap<S, S> = HashMap::new();
+    let mut queue: VecDeque<S> = VecDeque::new();
+    distances.insert(start.clone(), 0);
+    queue.push_back(start);
+
+    while let Some(state) = queue.pop_front() {
+        let cost = distances[&state];
+        for next in successors(&state) {
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), cost + 1);
+                predecessors.insert(next.clone(), state.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    Distances {
+        distances,
+        predecessors,
+    }
+}
+
+/// An entry in the priority queue, ordered so that `BinaryHeap` pops the lowest priority first.
+struct Entry<S, C> {
+    priority: C,
+    cost: C,
+    state: S,
+}
+
+impl<S, C: Ord> Ord for Entry<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .priority
+            .cmp(&self.priority)
+            .then_with(|| self.cost.cmp(&other.cost))
+    }
+}
+
+impl<S, C: Ord> PartialOrd for Entry<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S, C: Ord> PartialEq for Entry<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<S, C: Ord> Eq for Entry<S, C> {}
+
+/// Best-first search shared by Dijkstra and A*: expands states in order of cost plus heuristic
+/// until `stop` returns true for an expanded state, returning that state (if any) along with
+/// the distances and predecessors found so far.
+fn best_first<S, C, FN, I, FH, FS>(
+    start: S,
+    mut successors: FN,
+    mut heuristic: FH,
+    mut stop: FS,
+) -> (Option<S>, Distances<S, C>)
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    FN: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    FH: FnMut(&S) -> C,
+    FS: FnMut(&S) -> bool,
+{
+    let mut distances: HashMap<S, C> = HashMap::new();
+    let mut predecessors: HashMap<S, S> = HashMap::new();
+    let mut heap: BinaryHeap<Entry<S, C>> = BinaryHeap::new();
+    distances.insert(start.clone(), C::zero());
+    heap.push(Entry {
+        priority: heuristic(&start),
+        cost: C::zero(),
+        state: start,
+    });
+
+    while let Some(Entry { cost, state, .. }) = heap.pop() {
+        // If we already have a shorter path to this state, discard
+        if cost > distances[&state] {
+            continue;
+        }
+
+        if stop(&state) {
+            return (
+                Some(state),
+                Distances {
+                    distances,
+                    predecessors,
+                },
+            );
+        }
+
+        // For each possible next state, if we now have a lower-cost (or only) path to that state,
+        // keep the path
+        for (next, step_cost) in successors(&state) {
+            let next_cost = cost + step_cost;
+            match distances.get(&next) {
+                Some(&existing) if next_cost >= existing => {}
+                _ => {
+                    distances.insert(next.clone(), next_cost);
+                    predecessors.insert(next.clone(), state.clone());
+                    heap.push(Entry {
+                        priority: next_cost + heuristic(&next),
+                        cost: next_cost,
+                        state: next,
+                    });
+                }
+            }
+        }
+    }
+
+    (
+        None,
+        Distances {
+            distances,
+            predecessors,
+        },
+    )
+}
+
+/// Find the lowest-cost path from `start` to a state satisfying `is_goal`, where `successors`
+/// gives the next states and the cost of moving to each.
+pub fn dijkstra<S, C, FN, I, FG>(
+    start: S,
+    successors: FN,
+    is_goal: FG,
+) -> Option<SearchResult<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    FN: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    FG: FnMut(&S) -> bool,
+{
+    astar(start, successors, |_| C::zero(), is_goal)
+}
+
+/// Like `dijkstra`, but directed towards the goal by `heuristic`, which must never overestimate
+/// the remaining cost to reach a goal.
+pub fn astar<S, C, FN, I, FH, FG>(
+    start: S,
+    successors: FN,
+    heuristic: FH,
+    is_goal: FG,
+) -> Option<SearchResult<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    FN: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    FH: FnMut(&S) -> C,
+    FG: FnMut(&S) -> bool,
+{
+    let (goal, distances) = best_first(start, successors, heuristic, is_goal);
+    let goal = goal?;
+    Some(SearchResult {
+        cost: distances.distances[&goal],
+        goal,
+        predecessors: distances.predecessors,
+    })
+}
+
+/// Find the lowest-cost paths from `start` to every reachable state.
+pub fn dijkstra_all<S, C, FN, I>(start: S, successors: FN) -> Distances<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    FN: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+{
+    best_first(start, successors, |_| C::zero(), |_| false).1
+}
+
+/// Find the lowest-cost paths from `start` to each of `targets`, stopping once all of them have
+/// been reached. Targets that can't be reached are absent from the result, and distances to
+/// states other than the targets might not be the lowest.
+pub fn dijkstra_targets<S, C, FN, I, T>(start: S, successors: FN, targets: T) -> Distances<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    FN: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    T: IntoIterator<Item = S>,
+{
+    let mut remaining: HashSet<S> = targets.into_iter().collect();
+    best_first(
+        start,
+        successors,
+        |_| C::zero(),
+        |state| {
+            remaining.remove(state);
+            remaining.is_empty()
+        },
+    )
+    .1
+}
+
+/// Dial's algorithm: find the lowest-cost path from `start` to a state satisfying `is_goal`, where
+/// every step costs at most `max_weight`.
+///
+/// Instead of a heap, states are queued in a circular array of `max_weight + 1` buckets indexed by
+/// cost, and distances are kept in a dense array, so `index` must map every reachable state to a
+/// unique index in `0..size` (e.g. `Grid::row_major_index`).
+pub fn dial<S, FX, FN, I, FG>(
+    start: S,
+    size: usize,
+    max_weight: usize,
+    index: FX,
+    mut successors: FN,
+    mut is_goal: FG,
+) -> Option<usize>
+where
+    FX: Fn(&S) -> usize,
+    FN: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, usize)>,
+    FG: FnMut(&S) -> bool,
+{
+    let bucket_count = max_weight + 1;
+    let mut buckets: Vec<Vec<S>> = (0..bucket_count).map(|_| Vec::new()).collect();
+    let mut distances: Vec<usize> = vec![usize::MAX; size];
+    let mut queued: usize = 1;
+    distances[index(&start)] = 0;
+    buckets[0].push(start);
+
+    let mut cost = 0;
+    while queued > 0 {
+        let bucket = cost % bucket_count;
+        while let Some(state) = buckets[bucket].pop() {
+            queued -= 1;
+            // If we already have a shorter path to this state, discard
+            if distances[index(&state)] < cost {
+                continue;
+            }
+            if is_goal(&state) {
+                return Some(cost);
+            }
+            for (next, weight) in successors(&state) {
+                assert!(weight <= max_weight, "weight {} > {}", weight, max_weight);
+                let next_cost = cost + weight;
+                let i = index(&next);
+                if next_cost < distances[i] {
+                    distances[i] = next_cost;
+                    buckets[next_cost % bucket_count].push(next);
+                    queued += 1;
+                }
+            }
+        }
+        cost += 1;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A small weighted, directed graph:
+    ///
+    /// ```text
+    ///   a --1--> b --1--> c --1--> d
+    ///   |                          ^
+    ///   +------------5-------------+
+    ///   e (unreachable)
+    /// ```
+    fn successors(state: &char) -> Vec<(char, usize)> {
+        match state {
+            'a' => vec![('b', 1), ('d', 5)],
+            'b' => vec![('c', 1)],
+            'c' => vec![('d', 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_bfs() {
+        let unweighted = |s: &char| successors(s).into_iter().map(|(next, _)| next);
+        let result = bfs('a', unweighted, |&s| s == 'd').unwrap();
+        assert_eq!(result.cost, 1);
+        assert_eq!(result.path(), vec!['a', 'd']);
+        assert!(bfs('a', unweighted, |&s| s == 'e').is_none());
+
+        let all = bfs_all('a', unweighted);
+        assert_eq!(all.len(), 4);
+        assert_eq!(all.get(&'c'), Some(2));
+        assert_eq!(all.path_to(&'c'), Some(vec!['a', 'b', 'c']));
+        assert_eq!(all.path_to(&'e'), None);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let result = dijkstra('a', successors, |&s| s == 'd').unwrap();
+        assert_eq!(result.goal, 'd');
+        assert_eq!(result.cost, 3);
+        assert_eq!(result.path(), vec!['a', 'b', 'c', 'd']);
+        assert!(dijkstra('a', successors, |&s| s == 'e').is_none());
+
+        let all = dijkstra_all('a', successors);
+        assert_eq!(all.get(&'a'), Some(0));
+        assert_eq!(all.get(&'d'), Some(3));
+        assert_eq!(all.path_to(&'d'), Some(vec!['a', 'b', 'c', 'd']));
+
+        let targets = dijkstra_targets('a', successors, ['c', 'b']);
+        assert_eq!(targets.get(&'b'), Some(1));
+        assert_eq!(targets.get(&'c'), Some(2));
+        let targets = dijkstra_targets('a', successors, ['d', 'e']);
+        assert_eq!(targets.get(&'d'), Some(3));
+        assert_eq!(targets.get(&'e'), None);
+    }
+
+    #[test]
+    fn test_astar() {
+        // Shortest path across an open 2D grid, using Manhattan distance as the heuristic
+        type Point = (i64, i64);
+        let end: Point = (7, 5);
+        let successors = |&(x, y): &Point| {
+            [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+                .into_iter()
+                .filter(|&(x, y)| (0..10).contains(&x) && (0..10).contains(&y))
+                .map(|p| (p, 1))
+        };
+        let heuristic = |&(x, y): &Point| (end.0 - x).abs() + (end.1 - y).abs();
+        let result = astar((0, 0), successors, heuristic, |&p| p == end).unwrap();
+        assert_eq!(result.cost, 12);
+        assert_eq!(result.path().len(), 13);
+        assert_eq!(result.path().first(), Some(&(0, 0)));
+        assert_eq!(result.path().last(), Some(&end));
+    }
+
+    #[test]
+    fn test_dial() {
+        let index = |&s: &char| (s as u8 - b'a') as usize;
+        assert_eq!(dial('a', 5, 5, index, successors, |&s| s == 'd'), Some(3));
+        assert_eq!(dial('a', 5, 5, index, successors, |&s| s == 'a'), Some(0));
+        assert_eq!(dial('a', 5, 5, index, successors, |&s| s == 'e'), None);
+    }
+}
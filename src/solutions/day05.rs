@@ -1,68 +1,16 @@
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 
 use super::prelude::*;
 use crate::error::ParseError;
+use crate::geometry::{Intersection, Segment};
 use crate::util::{parse_lines, read_file, Counter};
 use crate::vector::Vector;
 
 type Point = Vector<i16, 2>;
 
-#[derive(Debug, Eq, PartialEq)]
-enum Orientation {
-    Diagonal,
-    Horizontal,
-    Vertical,
-}
-
 #[derive(Debug)]
-struct Line {
-    start: Point,
-    end: Point,
-}
-
-impl Line {
-    fn orientation(&self) -> Orientation {
-        match (
-            (self.end[0] - self.start[0]).abs(),
-            (self.start[1] - self.end[1]).abs(),
-        ) {
-            (0, _) => Orientation::Vertical,
-            (_, 0) => Orientation::Horizontal,
-            (a, b) if a == b => Orientation::Diagonal,
-            _ => panic!("non-45-degree diagonal"),
-        }
-    }
-
-    fn points(&self) -> Box<dyn Iterator<Item = Point>> {
-        match self.orientation() {
-            Orientation::Diagonal => {
-                let start = self.start;
-                let direction: Point = From::from([
-                    (self.end[0] - self.start[0]).signum(),
-                    (self.end[1] - self.start[1]).signum(),
-                ]);
-                let steps = (self.end[0] - self.start[0]).abs();
-                Box::new((0..=steps).map(move |i| start + direction * [i, i]))
-            }
-            Orientation::Horizontal => {
-                let y = self.start[1];
-                let (mut start, mut end) = (self.start[0], self.end[0]);
-                if start > end {
-                    std::mem::swap(&mut start, &mut end);
-                }
-                Box::new((start..=end).map(move |x| [x, y].into()))
-            }
-            Orientation::Vertical => {
-                let x = self.start[0];
-                let (mut start, mut end) = (self.start[1], self.end[1]);
-                if start > end {
-                    std::mem::swap(&mut start, &mut end);
-                }
-                Box::new((start..=end).map(move |y| [x, y].into()))
-            }
-        }
-    }
-}
+struct Line(Segment<i16, 2>);
 
 impl FromStr for Line {
     type Err = ParseError;
@@ -71,20 +19,20 @@ impl FromStr for Line {
         let (start, end) = s
             .split_once(" -> ")
             .ok_or(ParseError::Other("expected ' -> '".into()))?;
-        Ok(Line {
-            start: start.parse()?,
-            end: end.parse()?,
-        })
+        Ok(Line(Segment::new(
+            start.parse::<Point>()?,
+            end.parse::<Point>()?,
+        )))
     }
 }
 
 fn part1<R: BufRead>(reader: R) -> crate::Result<String> {
     let data: Vec<Line> = parse_lines(reader)
-        .filter(|line: &Line| line.orientation() != Orientation::Diagonal)
+        .filter(|line: &Line| line.0.is_axis_aligned())
         .collect();
     let mut counter = Counter::new();
     for line in data.iter() {
-        counter.count(line.points());
+        counter.count(line.0.points());
     }
     let count = counter.iter().filter(|(_, &v)| v > 1).count();
     Ok(count.to_string())
@@ -94,16 +42,137 @@ fn part2<R: BufRead>(reader: R) -> crate::Result<String> {
     let data: Vec<Line> = parse_lines(reader).collect();
     let mut counter = Counter::new();
     for line in data.iter() {
-        counter.count(line.points());
+        counter.count(line.0.points());
     }
     let count = counter.iter().filter(|(_, &v)| v > 1).count();
     Ok(count.to_string())
 }
 
+type FamilyKey = (Vector<i64, 2>, i64);
+
+/// The segments lying on one infinite line, identified by the line's direction and the cross
+/// product of that with any point on it. Points on the line are ordered by their `axis`
+/// coordinate, which changes by `stride` between neighbouring lattice points.
+#[derive(Default)]
+struct Family {
+    axis: usize,
+    stride: i64,
+    ranges: Vec<(i64, i64)>,
+    overlaps: Vec<(i64, i64)>,
+}
+
+impl Family {
+    /// Find the ranges covered by at least two segments, returning the number of points in them.
+    fn find_overlaps(&mut self) -> usize {
+        let mut events: Vec<(i64, i32)> = self
+            .ranges
+            .iter()
+            .flat_map(|&(lo, hi)| [(lo, 1), (hi + self.stride, -1)])
+            .collect();
+        events.sort_unstable();
+        let (mut depth, mut open) = (0, 0);
+        for (position, delta) in events {
+            let before = depth;
+            depth += delta;
+            if before < 2 && depth >= 2 {
+                open = position;
+            } else if before >= 2 && depth < 2 {
+                self.overlaps.push((open, position - self.stride));
+            }
+        }
+        self.overlaps
+            .iter()
+            .map(|&(lo, hi)| ((hi - lo) / self.stride) as usize + 1)
+            .sum()
+    }
+
+    fn in_overlap(&self, point: &Vector<i64, 2>) -> bool {
+        let position = point[self.axis];
+        let i = self.overlaps.partition_point(|&(lo, _)| lo <= position);
+        i > 0 && self.overlaps[i - 1].1 >= position
+    }
+}
+
+/// Count the points covered by at least two lines without visiting every point: overlaps of
+/// lines on the same infinite line are found by sweeping along it, and the remaining points are
+/// where lines on different infinite lines cross. A crossing can also lie in the overlaps of
+/// several infinite lines, so those are corrected for to count each point once.
+fn count_overlaps_sweep(lines: &[Line]) -> usize {
+    let mut families: HashMap<FamilyKey, Family> = HashMap::new();
+    let mut keys = Vec::with_capacity(lines.len());
+    for line in lines {
+        let (start, end) = (line.0.start.cast::<i64>(), line.0.end.cast::<i64>());
+        let mut step = Segment::new(start, end).step();
+        if step == Vector::default() {
+            step = [1, 0].into();
+        } else if step[0] < 0 || (step[0] == 0 && step[1] < 0) {
+            step = -step;
+        }
+        let key = (step, step[0] * start[1] - step[1] * start[0]);
+        let axis = if step[0] != 0 { 0 } else { 1 };
+        let family = families.entry(key).or_insert_with(|| Family {
+            axis,
+            stride: step[axis],
+            ..Family::default()
+        });
+        family
+            .ranges
+            .push((start[axis].min(end[axis]), start[axis].max(end[axis])));
+        keys.push(key);
+    }
+
+    let mut count: usize = families.values_mut().map(Family::find_overlaps).sum();
+
+    // For each crossing, the lines through it on which it was already counted as an overlap
+    let mut crossings: HashMap<Vector<i64, 2>, HashSet<FamilyKey>> = HashMap::new();
+    for (i, a) in lines.iter().enumerate() {
+        for (j, b) in lines.iter().enumerate().skip(i + 1) {
+            if keys[i] == keys[j] {
+                continue;
+            }
+            if let Some(Intersection::Point(point)) = a.0.intersection(&b.0) {
+                let point = point.cast::<i64>();
+                let counted = crossings.entry(point).or_default();
+                for key in [keys[i], keys[j]] {
+                    if families[&key].in_overlap(&point) {
+                        counted.insert(key);
+                    }
+                }
+            }
+        }
+    }
+    for counted in crossings.values() {
+        if counted.is_empty() {
+            count += 1;
+        } else {
+            count -= counted.len() - 1;
+        }
+    }
+    count
+}
+
+fn part1_sweep<R: BufRead>(reader: R) -> crate::Result<String> {
+    let data: Vec<Line> = parse_lines(reader)
+        .filter(|line: &Line| line.0.is_axis_aligned())
+        .collect();
+    Ok(count_overlaps_sweep(&data).to_string())
+}
+
+fn part2_sweep<R: BufRead>(reader: R) -> crate::Result<String> {
+    let data: Vec<Line> = parse_lines(reader).collect();
+    Ok(count_overlaps_sweep(&data).to_string())
+}
+
 pub fn build_runner() -> crate::Runner {
     let mut runner = crate::Runner::default();
     runner.add_fn("part1", || part1(read_file("data/day05_input.txt")));
     runner.add_fn("part2", || part2(read_file("data/day05_input.txt")));
+    runner.add_fn("part1_sweep", || {
+        part1_sweep(read_file("data/day05_input.txt"))
+    });
+    runner.add_fn("part2_sweep", || {
+        part2_sweep(read_file("data/day05_input.txt"))
+    });
     runner
 }
 
@@ -155,4 +224,57 @@ mod tests {
         );
         assert_eq!(part2(read_file("data/day05_input.txt")).unwrap(), "21373");
     }
+
+    #[test]
+    fn test_any_slope() {
+        assert_eq!(
+            part2(read_str(indoc! {"\
+                0,0 -> 4,2
+                2,0 -> 2,3
+                6,3 -> 0,0
+            "}))
+            .unwrap(),
+            "3"
+        );
+    }
+
+    #[test]
+    fn test_sweep() {
+        let example = indoc! {"\
+            0,9 -> 5,9
+            8,0 -> 0,8
+            9,4 -> 3,4
+            2,2 -> 2,1
+            7,0 -> 7,4
+            6,4 -> 2,0
+            0,9 -> 2,9
+            3,4 -> 1,4
+            0,0 -> 8,8
+            5,5 -> 8,2
+        "};
+        assert_eq!(part1_sweep(read_str(example)).unwrap(), "5");
+        assert_eq!(part2_sweep(read_str(example)).unwrap(), "12");
+        assert_eq!(
+            part1_sweep(read_file("data/day05_input.txt")).unwrap(),
+            part1(read_file("data/day05_input.txt")).unwrap()
+        );
+        assert_eq!(
+            part2_sweep(read_file("data/day05_input.txt")).unwrap(),
+            part2(read_file("data/day05_input.txt")).unwrap()
+        );
+
+        // Any slope, with a crossing inside a collinear overlap and a crossing between points
+        let lines = indoc! {"\
+            0,0 -> 4,2
+            2,0 -> 2,3
+            6,3 -> 0,0
+            0,3 -> 3,0
+            1,1 -> 1,1
+            0,1 -> 1,0
+        "};
+        assert_eq!(
+            part2_sweep(read_str(lines)).unwrap(),
+            part2(read_str(lines)).unwrap()
+        );
+    }
 }
@@ -1,50 +1,31 @@
-use std::collections::VecDeque;
-
 use super::prelude::*;
-use crate::grid;
+use crate::grid::{Automaton, Neighbourhood};
 use crate::util::{read_file, read_number_grid};
 
-type Grid = grid::Grid<u8, 2>;
-type Point = grid::Point<2>;
-
 struct State {
-    grid: Grid,
+    automaton: Automaton<u8, 2>,
 }
 
 impl State {
     fn from_reader<R: BufRead>(reader: R) -> State {
         State {
-            grid: read_number_grid(reader),
+            automaton: Automaton::new(read_number_grid(reader), Neighbourhood::FULL),
         }
     }
 
     fn step(&mut self) -> u64 {
-        let mut flashes: u64 = 0;
-        let mut flashing: VecDeque<Point> = VecDeque::new();
-        for p in self.grid.iter_points() {
-            self.grid[p] += 1;
-            // If this octopus went over energy 9, flash, potentially starting a chain reaction
-            if self.grid[p] == 10 {
-                flashing.push_back(p);
-            }
-            // Processing flashing octopuses until the chain reaction stops
-            while let Some(p) = flashing.pop_front() {
-                flashes += 1;
-                for adj in self.grid.iter_adjacent_8_points(p) {
-                    self.grid[adj] += 1;
-                    if self.grid[adj] == 10 {
-                        flashing.push_back(adj);
-                    }
-                }
-            }
-        }
+        // Increase every octopus's energy, and if it goes over energy 9 then flash, increasing
+        // the energy of adjacent octopuses and potentially starting a chain reaction
+        let increase = |_, energy: &mut u8| {
+            *energy += 1;
+            *energy == 10
+        };
+        let flashed = self.automaton.cascade(increase, increase);
         // Reset any octopuses that flashed during this step
-        for p in self.grid.iter_points() {
-            if self.grid[p] > 9 {
-                self.grid[p] = 0;
-            }
+        for p in flashed.iter().copied() {
+            self.automaton.grid_mut()[p] = 0;
         }
-        flashes
+        flashed.len() as u64
     }
 }
 
@@ -1,33 +1,14 @@
-use std::collections::{HashMap, HashSet};
-use std::rc::Rc;
-use std::str::FromStr;
+use std::collections::HashMap;
 
 use super::prelude::*;
-use crate::error::ParseError;
-use crate::util::{parse_lines, read_file};
-
-type Node = Rc<String>;
-
-fn is_large(cave: &str) -> bool {
-    cave.as_bytes()[0].is_ascii_uppercase()
-}
-
-struct Edge(Node, Node);
-
-impl FromStr for Edge {
-    type Err = ParseError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (a, b) = s.split_once('-').unwrap();
-        Ok(Edge(Node::new(a.to_owned()), Node::new(b.to_owned())))
-    }
-}
+use crate::graph::{Graph, NodeId};
+use crate::util::read_file;
 
 #[derive(Clone, Debug)]
-struct Path(Vec<Node>, u8);
+struct Path(Vec<NodeId>, u8);
 
 impl Path {
-    fn new(initial: Node) -> Path {
+    fn new(initial: NodeId) -> Path {
         Path(vec![initial], 0)
     }
 
@@ -35,75 +16,153 @@ impl Path {
         Path(self.0, n)
     }
 
-    fn last(&self) -> &Node {
-        self.0.last().unwrap()
-    }
-
-    fn try_add(&self, node: Node) -> Option<Path> {
-        let mut next = self.clone();
-        if is_large(&node) {
-            next.0.push(node);
-        } else if !next.0.contains(&node) {
-            next.0.push(node);
-        } else if next.1 > 0 {
-            next.1 -= 1;
-            next.0.push(node);
-        } else {
-            return None;
-        }
-        Some(next)
+    fn last(&self) -> NodeId {
+        *self.0.last().unwrap()
     }
 }
 
 struct CaveMap {
-    edges: HashMap<Node, HashSet<Node>>,
+    graph: Graph,
+    large: Vec<bool>,
+    /// The bit representing each small cave in a set of visited caves.
+    small_bits: Vec<Option<u32>>,
+    start: NodeId,
+    end: NodeId,
 }
 
 impl CaveMap {
     fn from_reader<R: BufRead>(reader: R) -> CaveMap {
-        // Parse all edges as bidirectional
-        let mut edges: HashMap<Node, HashSet<Node>> = HashMap::new();
-        for Edge(a, b) in parse_lines::<Edge, R>(reader) {
-            edges.entry(a.clone()).or_default().insert(b.clone());
-            edges.entry(b).or_default().insert(a);
-        }
+        let mut graph = Graph::from_edge_list(reader, false).unwrap();
+        let large: Vec<bool> = graph
+            .nodes()
+            .map(|id| graph.name(id).as_bytes()[0].is_ascii_uppercase())
+            .collect();
+        let mut small_count = 0;
+        let small_bits = large
+            .iter()
+            .map(|&is_large| {
+                (!is_large).then(|| {
+                    small_count += 1;
+                    small_count - 1
+                })
+            })
+            .collect();
+        let start = graph.id("start").unwrap();
+        let end = graph.id("end").unwrap();
 
         // Remove edges towards "start" or away from "end", since both can only be visited once
-        edges.remove(&Node::new("end".into()));
-        for (_, next) in edges.iter_mut() {
-            next.remove(&Node::new("start".into()));
+        graph.retain_edges(|from, to, _| from != end && to != start);
+
+        CaveMap {
+            graph,
+            large,
+            small_bits,
+            start,
+            end,
         }
+    }
 
-        CaveMap { edges }
+    fn try_add(&self, path: &Path, node: NodeId) -> Option<Path> {
+        let mut next = path.clone();
+        if self.large[node] || !next.0.contains(&node) {
+            next.0.push(node);
+        } else if next.1 > 0 {
+            next.1 -= 1;
+            next.0.push(node);
+        } else {
+            return None;
+        }
+        Some(next)
     }
 
     fn iter_paths(&self, path: Path) -> Box<dyn Iterator<Item = Path> + '_> {
-        match self.edges.get(path.last()) {
-            Some(candidates) => Box::new(
-                candidates
-                    .iter()
-                    .filter_map(move |next| {
-                        path.try_add(next.clone())
+        if path.last() == self.end {
+            Box::new(std::iter::once(path))
+        } else {
+            Box::new(
+                self.graph
+                    .neighbours(path.last())
+                    .filter_map(move |(next, _)| {
+                        self.try_add(&path, next)
                             .map(|next_path| self.iter_paths(next_path))
                     })
                     .flatten(),
-            ),
-            None => Box::new(std::iter::once(path)),
+            )
         }
     }
 
     fn iter_all_paths(&self, revisits: u8) -> Box<dyn Iterator<Item = Path> + '_> {
-        let initial = Path::new(Node::new("start".into())).with_revisits(revisits);
+        let initial = Path::new(self.start).with_revisits(revisits);
         self.iter_paths(initial)
     }
+
+    /// Count the paths from "start" to "end" without enumerating them.
+    ///
+    /// The number of ways to finish a path only depends on the current cave, which small caves
+    /// have already been visited, and how many revisits remain, so the count for each of those
+    /// combinations can be memoized.
+    fn count_all_paths(&self, revisits: u8) -> u64 {
+        let small_count = self.small_bits.iter().flatten().count();
+        assert!(
+            small_count <= 64,
+            "too many small caves for visited bitmask"
+        );
+        let mut memo: HashMap<(NodeId, u64, u8), u64> = HashMap::new();
+
+        fn count(
+            cave_map: &CaveMap,
+            node: NodeId,
+            visited: u64,
+            revisits: u8,
+            memo: &mut HashMap<(NodeId, u64, u8), u64>,
+        ) -> u64 {
+            if node == cave_map.end {
+                1
+            } else if let Some(result) = memo.get(&(node, visited, revisits)).copied() {
+                result
+            } else {
+                let result = cave_map
+                    .graph
+                    .neighbours(node)
+                    .map(|(next, _)| {
+                        let Some(bit) = cave_map.small_bits[next].map(|i| 1 << i) else {
+                            return count(cave_map, next, visited, revisits, memo);
+                        };
+                        if visited & bit == 0 {
+                            count(cave_map, next, visited | bit, revisits, memo)
+                        } else if revisits > 0 {
+                            count(cave_map, next, visited, revisits - 1, memo)
+                        } else {
+                            0
+                        }
+                    })
+                    .sum();
+                memo.insert((node, visited, revisits), result);
+                result
+            }
+        }
+
+        let visited = 1 << self.small_bits[self.start].unwrap();
+        count(self, self.start, visited, revisits, &mut memo)
+    }
 }
 
 fn part1<R: BufRead>(reader: R) -> crate::Result<String> {
     let cave_map = CaveMap::from_reader(reader);
-    Ok(cave_map.iter_all_paths(0).count().to_string())
+    Ok(cave_map.count_all_paths(0).to_string())
 }
 
 fn part2<R: BufRead>(reader: R) -> crate::Result<String> {
+    let cave_map = CaveMap::from_reader(reader);
+    Ok(cave_map.count_all_paths(1).to_string())
+}
+
+fn part1_enumerate<R: BufRead>(reader: R) -> crate::Result<String> {
+    let cave_map = CaveMap::from_reader(reader);
+    Ok(cave_map.iter_all_paths(0).count().to_string())
+}
+
+fn part2_enumerate<R: BufRead>(reader: R) -> crate::Result<String> {
     let cave_map = CaveMap::from_reader(reader);
     Ok(cave_map.iter_all_paths(1).count().to_string())
 }
@@ -112,12 +171,19 @@ pub fn build_runner() -> crate::Runner {
     let mut runner = crate::Runner::default();
     runner.add_fn("part1", || part1(read_file("data/day12_input.txt")));
     runner.add_fn("part2", || part2(read_file("data/day12_input.txt")));
+    runner.add_fn("part1_enumerate", || {
+        part1_enumerate(read_file("data/day12_input.txt"))
+    });
+    runner.add_fn("part2_enumerate", || {
+        part2_enumerate(read_file("data/day12_input.txt"))
+    });
     runner
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::util::read_str;
 
     #[test]
     fn test_part1() {
@@ -134,4 +200,68 @@ mod tests {
         assert_eq!(part2(read_file("data/day12_example3.txt")).unwrap(), "3509");
         assert_eq!(part2(read_file("data/day12_input.txt")).unwrap(), "131254");
     }
+
+    #[test]
+    fn test_part1_enumerate() {
+        assert_eq!(
+            part1_enumerate(read_file("data/day12_example1.txt")).unwrap(),
+            "10"
+        );
+        assert_eq!(
+            part1_enumerate(read_file("data/day12_example2.txt")).unwrap(),
+            "19"
+        );
+        assert_eq!(
+            part1_enumerate(read_file("data/day12_example3.txt")).unwrap(),
+            "226"
+        );
+        assert_eq!(
+            part1_enumerate(read_file("data/day12_input.txt")).unwrap(),
+            "4495"
+        );
+    }
+
+    #[test]
+    fn test_part2_enumerate() {
+        assert_eq!(
+            part2_enumerate(read_file("data/day12_example1.txt")).unwrap(),
+            "36"
+        );
+        assert_eq!(
+            part2_enumerate(read_file("data/day12_example2.txt")).unwrap(),
+            "103"
+        );
+        assert_eq!(
+            part2_enumerate(read_file("data/day12_example3.txt")).unwrap(),
+            "3509"
+        );
+        assert_eq!(
+            part2_enumerate(read_file("data/day12_input.txt")).unwrap(),
+            "131254"
+        );
+    }
+
+    #[test]
+    fn test_many_large_caves() {
+        // Only small caves need a bit in the visited set, so there can be more than 64 caves
+        let input: String = (0..100)
+            .map(|i| format!("start-L{0}\nL{0}-end\nL{0}-c\n", i))
+            .collect();
+        let cave_map = CaveMap::from_reader(read_str(&input));
+        assert!(cave_map.graph.len() > 64);
+        assert_eq!(cave_map.count_all_paths(0), 100 + 100 * 100);
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let cave_map = CaveMap::from_reader(read_file("data/day12_example1.txt"));
+        let path = cave_map
+            .iter_all_paths(0)
+            .min_by_key(|path| path.0.len())
+            .unwrap();
+        let dot = cave_map.graph.to_dot(Some(&path.0));
+        assert!(dot.starts_with("graph {\n"));
+        assert!(dot.contains("  \"start\" [color=red];\n"));
+        assert!(dot.contains("  \"A\" -- \"end\" [color=red];\n"));
+    }
 }
@@ -1,16 +1,17 @@
-use std::collections::{HashSet, VecDeque};
+use std::collections::VecDeque;
 use std::str::FromStr;
 
 use itertools::Itertools;
 
 use super::prelude::*;
 use crate::error::ParseError;
+use crate::grid;
 use crate::util::{parse_lines, read_file};
-use crate::vector::Vector;
 
-type Point = Vector<u16, 2>;
+type Point = grid::Point<2>;
+type SparseGrid = grid::SparseGrid<(), 2>;
 
-fn fold_coordinate(a: u16, f: u16) -> u16 {
+fn fold_coordinate(a: i64, f: i64) -> i64 {
     if a < f {
         a
     } else {
@@ -20,8 +21,8 @@ fn fold_coordinate(a: u16, f: u16) -> u16 {
 
 #[derive(Clone, Copy, Debug)]
 enum Fold {
-    X(u16),
-    Y(u16),
+    X(i64),
+    Y(i64),
 }
 
 impl Fold {
@@ -50,7 +51,7 @@ impl FromStr for Fold {
 
 #[derive(Clone)]
 struct Page {
-    dots: HashSet<Point>,
+    dots: SparseGrid,
     folds: VecDeque<Fold>,
 }
 
@@ -60,7 +61,7 @@ impl Page {
         let (split, _) = lines.iter().find_position(|line| line.len() == 0).unwrap();
         let dots = lines[0..split]
             .iter()
-            .map(|line| line.parse().unwrap())
+            .map(|line| (line.parse().unwrap(), ()))
             .collect();
         let folds = lines[(split + 1)..]
             .iter()
@@ -77,9 +78,8 @@ impl Page {
         if let Some(fold) = self.folds.pop_front() {
             let new = self
                 .dots
-                .iter()
-                .copied()
-                .map(|point| fold.apply(point))
+                .iter_points()
+                .map(|point| (fold.apply(point), ()))
                 .collect();
             self.dots = new;
             true
@@ -89,23 +89,8 @@ impl Page {
     }
 
     fn to_string(&self) -> String {
-        let extent = self
-            .dots
-            .iter()
-            .fold(Point::from([0, 0]), |acc, dot| acc.merge_max(dot))
-            + [1, 1];
-        let mut output = String::with_capacity((extent[0] as usize + 1) * extent[1] as usize);
-        for y in 0..extent[1] {
-            for x in 0..extent[0] {
-                if self.dots.contains(&Point::from([x, y])) {
-                    output.push('#');
-                } else {
-                    output.push(' ');
-                }
-            }
-            output.push('\n');
-        }
-        output
+        self.dots
+            .render(|dot| if dot.is_some() { '#' } else { ' ' })
     }
 }
 
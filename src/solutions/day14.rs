@@ -35,6 +35,37 @@ impl Polymer {
         next.push(prev[prev.len() - 1]);
         next
     }
+
+    /// Count how many times each pair of adjacent elements occurs after `steps` steps.
+    ///
+    /// The result of each insertion only depends on the pair it's inserted into, so pairs can be
+    /// counted without building the polymer, which doubles in length each step.
+    fn pair_counts(&self, steps: usize) -> Counter<(u8, u8), u128> {
+        let mut counts: Counter<(u8, u8), u128> =
+            self.template.iter().copied().tuple_windows().collect();
+        for _ in 0..steps {
+            let mut next = Counter::default();
+            for ((a, b), n) in counts.into_iter() {
+                let insert = self.rules.get(&(a, b)).copied().unwrap();
+                next.add((a, insert), n);
+                next.add((insert, b), n);
+            }
+            counts = next;
+        }
+        counts
+    }
+
+    /// Count how many times each element occurs after `steps` steps.
+    fn element_counts(&self, steps: usize) -> Counter<u8, u128> {
+        // Pairs overlap, so only count the first element of each pair, plus the last element of
+        // the polymer which never changes
+        let mut counts = Counter::default();
+        for ((a, _), n) in self.pair_counts(steps) {
+            counts.add(a, n);
+        }
+        counts.add(*self.template.last().unwrap(), 1);
+        counts
+    }
 }
 
 fn part1<R: BufRead>(reader: R) -> crate::Result<String> {
@@ -44,16 +75,16 @@ fn part1<R: BufRead>(reader: R) -> crate::Result<String> {
     })
     .nth(10)
     .unwrap();
-    let mut counter = Counter::new();
-    counter.count(last.iter().copied());
-    let mut counts: Vec<_> = counter.into_iter().collect();
-    counts.sort_by_key(|(_, k)| *k);
-    let result = counts.last().unwrap().1 - counts.first().unwrap().1;
+    let counter: Counter<u8> = last.iter().copied().collect();
+    let result = counter.max_entry().unwrap().1 - counter.min_entry().unwrap().1;
     Ok(result.to_string())
 }
 
-fn part2<R: BufRead>(_reader: R) -> crate::Result<String> {
-    Err(crate::Error::Other("not implemented".into()))
+fn part2<R: BufRead>(reader: R) -> crate::Result<String> {
+    let polymer = Polymer::from_reader(reader);
+    let counts = polymer.element_counts(40);
+    let result = counts.max_entry().unwrap().1 - counts.min_entry().unwrap().1;
+    Ok(result.to_string())
 }
 
 pub fn build_runner() -> crate::Runner {
@@ -75,7 +106,34 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(read_file("data/day14_example1.txt")).unwrap(), "???");
-        assert_eq!(part2(read_file("data/day14_input.txt")).unwrap(), "???");
+        assert_eq!(
+            part2(read_file("data/day14_example1.txt")).unwrap(),
+            "2188189693529"
+        );
+        assert_eq!(
+            part2(read_file("data/day14_input.txt")).unwrap(),
+            "2967977072188"
+        );
+    }
+
+    #[test]
+    fn test_element_counts() {
+        let polymer = Polymer::from_reader(read_file("data/day14_example1.txt"));
+        let last = std::iter::successors(Some(polymer.template.clone()), |prev| {
+            Some(polymer.step(prev.as_ref()))
+        })
+        .nth(10)
+        .unwrap();
+        let counts = polymer.element_counts(10);
+        assert_eq!(last.len(), 3073);
+        assert_eq!(counts.values().sum::<u128>(), 3073);
+        assert_eq!(counts[&b'B'], 1749);
+        assert_eq!(counts[&b'C'], 298);
+        assert_eq!(counts[&b'H'], 161);
+        assert_eq!(counts[&b'N'], 865);
+        assert_eq!(
+            counts[&b'B'] as usize,
+            last.iter().filter(|&&e| e == b'B').count()
+        );
     }
 }
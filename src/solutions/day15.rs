@@ -1,16 +1,9 @@
-use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap};
-use std::fmt;
-use std::rc::Rc;
-
-use num::Integer;
-
 use super::prelude::*;
 use crate::grid;
+use crate::search;
 use crate::util::{read_file, read_number_grid};
 
 type Grid = grid::Grid<u8, 2>;
-type Point = grid::Point<2>;
 
 struct Cave {
     grid: Grid,
@@ -24,183 +17,82 @@ impl Cave {
     }
 
     fn full_size(&self) -> Cave {
-        let size = self.grid.size();
-        let mut grid = Grid::new(size * [5, 5]);
-        let points = grid.iter_points();
-        grid = grid.with_data(points.map(|p| {
-            let (x_quot, x_rem) = p[0].div_rem(&size[0]);
-            let (y_quot, y_rem) = p[1].div_rem(&size[1]);
-            (self.grid[[x_rem, y_rem]] + x_quot as u8 + y_quot as u8 - 1) % 9 + 1
-        }));
-        Cave { grid }
-    }
-}
-
-#[derive(Clone, Eq, PartialEq)]
-struct Path {
-    head: Point,
-    tail: Option<Rc<Path>>,
-}
-
-impl Path {
-    fn new(head: Point) -> Rc<Path> {
-        Rc::new(Path { head, tail: None })
-    }
-
-    fn append(self: &Rc<Self>, head: Point) -> Rc<Path> {
-        Rc::new(Path {
-            head,
-            tail: Some(self.clone()),
-        })
-    }
-
-    fn iter(self: &Rc<Self>) -> PathIter {
-        PathIter {
-            next: Some(self.clone()),
-        }
-    }
-}
-
-struct PathIter {
-    next: Option<Rc<Path>>,
-}
-
-impl Iterator for PathIter {
-    type Item = Point;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if let Some(path) = self.next.take() {
-            self.next = path.tail.clone();
-            Some(path.head)
-        } else {
-            None
-        }
-    }
-}
-
-#[derive(Clone, Eq, PartialEq)]
-struct State {
-    cost: usize,
-    path: Rc<Path>,
-}
-
-impl State {
-    fn new(start: Point) -> State {
-        State {
-            cost: 0,
-            path: Path::new(start),
-        }
-    }
-
-    #[inline]
-    fn position(&self) -> Point {
-        self.path.head
-    }
-
-    fn append(&self, point: Point, cost: usize) -> State {
-        State {
-            cost: cost + self.cost,
-            path: self.path.append(point),
+        Cave {
+            grid: self.grid.tile(5, 5, |tile, &risk| {
+                (risk + tile[0] as u8 + tile[1] as u8 - 1) % 9 + 1
+            }),
         }
     }
-}
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        // Reverse ordering, so BinaryHeap can be used as a priority queue with lowest cost first
-        other
-            .cost
-            .cmp(&self.cost)
-            .then_with(|| self.position().cmp(&other.position()))
+    fn lowest_total_risk(&self) -> Option<usize> {
+        let start = self.grid.min_point();
+        let end = self.grid.max_point();
+        // Every position has a risk of at least 1, so the Manhattan distance to the end is an
+        // admissible heuristic
+        let result = search::astar(
+            start,
+            |&point| {
+                self.grid
+                    .iter_adjacent_4_points(point)
+                    .map(|p| (p, self.grid[p] as usize))
+            },
+            |&point| (end - point).manhattan_length() as usize,
+            |&point| point == end,
+        )?;
+        Some(result.cost)
     }
-}
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    /// Risk levels are only 1-9, so a bucket queue can be used instead of a heap.
+    fn lowest_total_risk_bucket(&self) -> Option<usize> {
+        let start = self.grid.min_point();
+        let end = self.grid.max_point();
+        search::dial(
+            start,
+            self.grid.len(),
+            9,
+            |&point| self.grid.row_major_index(point).unwrap(),
+            |&point| {
+                self.grid
+                    .iter_adjacent_4_points(point)
+                    .map(|p| (p, self.grid[p] as usize))
+            },
+            |&point| point == end,
+        )
     }
 }
 
-impl fmt::Debug for State {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut path: Vec<_> = self.path.iter().collect();
-        path.reverse();
-        write!(f, "State {{ cost: {}, path: {:?} }}", self.cost, path)
-    }
+fn part1<R: BufRead>(reader: R) -> crate::Result<String> {
+    let cave = Cave::from_reader(reader);
+    Ok(cave.lowest_total_risk().unwrap().to_string())
 }
 
-// TODO: use A* to direct to a solution faster
-fn shortest_path_dijkstra<F, I>(start: Point, end: Point, next: F) -> Option<State>
-where
-    F: Fn(Point) -> I,
-    I: Iterator<Item = (Point, usize)>,
-{
-    let mut distance: HashMap<Point, usize> = HashMap::new();
-    distance.insert(start, 0);
-
-    let mut heap: BinaryHeap<State> = BinaryHeap::new();
-    heap.push(State::new(start));
-
-    // Expand the shortest path each time
-    while let Some(state) = heap.pop() {
-        // If we found the goal, return the successful shortest path
-        if state.position() == end {
-            return Some(state);
-        }
-
-        // If we already have a shorter path to this position, discard
-        if let Some(cost) = distance.get(&state.position()) {
-            if state.cost > *cost {
-                continue;
-            }
-        }
-
-        // For each possible next position, if we now have a lower-cost (or only) path to that
-        // position, keep the path
-        for (point, cost) in next(state.position()) {
-            let next_state = state.append(point, cost);
-            match distance.get(&next_state.position()) {
-                Some(cost) if next_state.cost >= *cost => {}
-                _ => {
-                    distance.insert(point, next_state.cost);
-                    heap.push(next_state);
-                }
-            }
-        }
-    }
-
-    None
+fn part2<R: BufRead>(reader: R) -> crate::Result<String> {
+    let small_cave = Cave::from_reader(reader);
+    let cave = small_cave.full_size();
+    Ok(cave.lowest_total_risk().unwrap().to_string())
 }
 
-fn part1<R: BufRead>(reader: R) -> crate::Result<String> {
+fn part1_bucket<R: BufRead>(reader: R) -> crate::Result<String> {
     let cave = Cave::from_reader(reader);
-    let start = cave.grid.min_point();
-    let end = cave.grid.max_point();
-    let path = shortest_path_dijkstra(start, end, |point| {
-        cave.grid
-            .iter_adjacent_4_points(point)
-            .map(|p| (p, cave.grid[p] as usize))
-    });
-    Ok(path.unwrap().cost.to_string())
+    Ok(cave.lowest_total_risk_bucket().unwrap().to_string())
 }
 
-fn part2<R: BufRead>(reader: R) -> crate::Result<String> {
+fn part2_bucket<R: BufRead>(reader: R) -> crate::Result<String> {
     let small_cave = Cave::from_reader(reader);
     let cave = small_cave.full_size();
-    let start = cave.grid.min_point();
-    let end = cave.grid.max_point();
-    let path = shortest_path_dijkstra(start, end, |point| {
-        cave.grid
-            .iter_adjacent_4_points(point)
-            .map(|p| (p, cave.grid[p] as usize))
-    });
-    Ok(path.unwrap().cost.to_string())
+    Ok(cave.lowest_total_risk_bucket().unwrap().to_string())
 }
 
 pub fn build_runner() -> crate::Runner {
     let mut runner = crate::Runner::default();
     runner.add_fn("part1", || part1(read_file("data/day15_input.txt")));
     runner.add_fn("part2", || part2(read_file("data/day15_input.txt")));
+    runner.add_fn("part1_bucket", || {
+        part1_bucket(read_file("data/day15_input.txt"))
+    });
+    runner.add_fn("part2_bucket", || {
+        part2_bucket(read_file("data/day15_input.txt"))
+    });
     runner
 }
 
@@ -252,4 +144,52 @@ mod tests {
         );
         assert_eq!(part2(read_file("data/day15_input.txt")).unwrap(), "2825");
     }
+
+    #[test]
+    fn test_part1_bucket() {
+        assert_eq!(
+            part1_bucket(read_str(indoc! {"\
+                1163751742
+                1381373672
+                2136511328
+                3694931569
+                7463417111
+                1319128137
+                1359912421
+                3125421639
+                1293138521
+                2311944581
+            "}))
+            .unwrap(),
+            "40"
+        );
+        assert_eq!(
+            part1_bucket(read_file("data/day15_input.txt")).unwrap(),
+            "447"
+        );
+    }
+
+    #[test]
+    fn test_part2_bucket() {
+        assert_eq!(
+            part2_bucket(read_str(indoc! {"\
+                1163751742
+                1381373672
+                2136511328
+                3694931569
+                7463417111
+                1319128137
+                1359912421
+                3125421639
+                1293138521
+                2311944581
+            "}))
+            .unwrap(),
+            "315"
+        );
+        assert_eq!(
+            part2_bucket(read_file("data/day15_input.txt")).unwrap(),
+            "2825"
+        );
+    }
 }
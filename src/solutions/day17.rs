@@ -0,0 +1,148 @@
+use std::str::FromStr;
+
+use super::prelude::*;
+use crate::error::ParseError;
+use crate::geometry::Aabb;
+use crate::util::{open_file, parse_range, read_line};
+use crate::vector::Vector;
+
+type Vec2 = Vector<i64, 2>;
+
+struct Target(Aabb<i64, 2>);
+
+impl FromStr for Target {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expected =
+            || ParseError::Other(format!("expected 'target area: x=.., y=..', got {:?}", s));
+        let (x, y) = s
+            .trim()
+            .strip_prefix("target area: ")
+            .and_then(|rest| rest.split_once(", "))
+            .ok_or_else(expected)?;
+        let x = parse_range(x.strip_prefix("x=").ok_or_else(expected)?)?;
+        let y = parse_range(y.strip_prefix("y=").ok_or_else(expected)?)?;
+        Ok(Target(Aabb::new([x.0, y.0], [x.1, y.1]).unwrap()))
+    }
+}
+
+impl Target {
+    /// Launch a probe with `velocity`, returning the highest point it reaches if it is ever inside
+    /// the target at the end of a step.
+    fn launch(&self, mut velocity: Vec2) -> Option<i64> {
+        let (min, max) = (self.0.min(), self.0.max());
+        let mut position = Vec2::default();
+        let mut highest = 0;
+        loop {
+            position += velocity;
+            velocity[0] -= velocity[0].signum();
+            velocity[1] -= 1;
+            highest = highest.max(position[1]);
+            if self.0.contains(position) {
+                return Some(highest);
+            }
+            // Once falling below the target, or stopped moving sideways outside it, it can never
+            // come back
+            let stalled = velocity[0] == 0 && (position[0] < min[0] || position[0] > max[0]);
+            if (velocity[1] < 0 && position[1] < min[1]) || stalled {
+                return None;
+            }
+        }
+    }
+
+    /// Iterate over every initial velocity that hits the target, along with the highest point
+    /// reached.
+    ///
+    /// The probe's first step is the whole initial velocity, so it can't be further from the
+    /// origin than the far edge of the target on either axis, and a probe launched upwards at
+    /// `vy` comes back down through `y = 0` at `-(vy + 1)`, which bounds upward launches too.
+    fn hits(&self) -> impl Iterator<Item = (Vec2, i64)> + '_ {
+        let (min, max) = (self.0.min(), self.0.max());
+        let reach = min[1].abs().max(max[1].abs());
+        (min[0].min(0)..=max[0].max(0))
+            .flat_map(move |vx| (-reach..=reach).map(move |vy| Vec2::from([vx, vy])))
+            .filter_map(|velocity| Some((velocity, self.launch(velocity)?)))
+    }
+
+    /// Find the highest point reachable while hitting a target below the launch point, if some
+    /// probe can stop moving sideways above the target and then fall vertically into it.
+    ///
+    /// A probe launched upwards at `vy` comes back down through `y = 0` at `-(vy + 1)`, and the
+    /// next step mustn't overshoot the bottom of the target, so the best is `vy = -min_y - 1`,
+    /// which reaches the triangular number `vy * (vy + 1) / 2`.
+    fn max_height_analytic(&self) -> Option<i64> {
+        let (min, max) = (self.0.min(), self.0.max());
+        if max[1] >= 0 {
+            return None;
+        }
+        // A probe launched sideways at `vx` stops after moving `vx * (vx + 1) / 2`
+        let direction = if max[0] < 0 { -1 } else { 1 };
+        let vx = (0..=min[0].abs().max(max[0].abs()))
+            .find(|vx| (min[0]..=max[0]).contains(&(direction * vx * (vx + 1) / 2)))?;
+        let vy = -min[1] - 1;
+        self.launch([direction * vx, vy].into())?;
+        Some(vy * (vy + 1) / 2)
+    }
+
+    fn max_height(&self) -> Option<i64> {
+        self.max_height_analytic()
+            .or_else(|| self.hits().map(|(_, height)| height).max())
+    }
+}
+
+fn part1<R: BufRead>(reader: R) -> crate::Result<String> {
+    let target: Target = read_line(reader).parse()?;
+    Ok(target.max_height().unwrap().to_string())
+}
+
+fn part2<R: BufRead>(reader: R) -> crate::Result<String> {
+    let target: Target = read_line(reader).parse()?;
+    Ok(target.hits().count().to_string())
+}
+
+pub fn build_runner() -> crate::Runner {
+    let mut runner = crate::Runner::default();
+    runner.add_fn("part1", || part1(open_file("data/day17_input.txt")?));
+    runner.add_fn("part2", || part2(open_file("data/day17_input.txt")?));
+    runner
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::read_str;
+
+    const EXAMPLE: &str = "target area: x=20..30, y=-10..-5\n";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(read_str(EXAMPLE)).unwrap(), "45");
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(read_str(EXAMPLE)).unwrap(), "112");
+    }
+
+    #[test]
+    fn test_launch() {
+        let target: Target = EXAMPLE.parse().unwrap();
+        assert_eq!(target.launch([7, 2].into()), Some(3));
+        assert_eq!(target.launch([6, 3].into()), Some(6));
+        assert_eq!(target.launch([9, 0].into()), Some(0));
+        assert_eq!(target.launch([17, -4].into()), None);
+        assert_eq!(target.launch([6, 9].into()), Some(45));
+        assert_eq!(
+            target.max_height_analytic(),
+            target.hits().map(|(_, height)| height).max()
+        );
+
+        let above: Target = "target area: x=5..10, y=3..6".parse().unwrap();
+        assert_eq!(above.max_height_analytic(), None);
+        assert_eq!(above.max_height(), above.hits().map(|(_, h)| h).max());
+        assert!(above.max_height().is_some());
+
+        assert!("target area: x=20..30".parse::<Target>().is_err());
+    }
+}
@@ -0,0 +1,303 @@
+use std::fmt;
+use std::ops;
+use std::str::FromStr;
+
+use super::prelude::*;
+use crate::error::ParseError;
+use crate::util::{open_file, parse_lines};
+
+/// A snailfish number: either a regular number or a pair of snailfish numbers.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Number {
+    Regular(u32),
+    Pair(Box<Number>, Box<Number>),
+}
+
+/// What an explosion deep inside a number still needs to do: the values to add to the nearest
+/// regular numbers to its left and right, which are taken once they've been added.
+struct Explosion(Option<u32>, Option<u32>);
+
+impl Number {
+    fn pair(left: Number, right: Number) -> Number {
+        Number::Pair(Box::new(left), Box::new(right))
+    }
+
+    fn magnitude(&self) -> u32 {
+        match self {
+            Number::Regular(value) => *value,
+            Number::Pair(left, right) => 3 * left.magnitude() + 2 * right.magnitude(),
+        }
+    }
+
+    fn add_leftmost(&mut self, value: u32) {
+        match self {
+            Number::Regular(v) => *v += value,
+            Number::Pair(left, _) => left.add_leftmost(value),
+        }
+    }
+
+    fn add_rightmost(&mut self, value: u32) {
+        match self {
+            Number::Regular(v) => *v += value,
+            Number::Pair(_, right) => right.add_rightmost(value),
+        }
+    }
+
+    /// Explode the leftmost pair nested inside four pairs, if there is one.
+    fn explode(&mut self) -> bool {
+        self.explode_at(0).is_some()
+    }
+
+    fn explode_at(&mut self, depth: usize) -> Option<Explosion> {
+        let Number::Pair(left, right) = self else {
+            return None;
+        };
+        if depth >= 4 {
+            if let (Number::Regular(l), Number::Regular(r)) = (left.as_ref(), right.as_ref()) {
+                let explosion = Explosion(Some(*l), Some(*r));
+                *self = Number::Regular(0);
+                return Some(explosion);
+            }
+        }
+        if let Some(Explosion(carry_left, carry_right)) = left.explode_at(depth + 1) {
+            if let Some(value) = carry_right {
+                right.add_leftmost(value);
+            }
+            return Some(Explosion(carry_left, None));
+        }
+        if let Some(Explosion(carry_left, carry_right)) = right.explode_at(depth + 1) {
+            if let Some(value) = carry_left {
+                left.add_rightmost(value);
+            }
+            return Some(Explosion(None, carry_right));
+        }
+        None
+    }
+
+    /// Split the leftmost regular number of 10 or more, if there is one.
+    fn split(&mut self) -> bool {
+        match self {
+            Number::Regular(value) if *value >= 10 => {
+                let half = *value / 2;
+                *self = Number::pair(Number::Regular(half), Number::Regular(*value - half));
+                true
+            }
+            Number::Regular(_) => false,
+            Number::Pair(left, right) => left.split() || right.split(),
+        }
+    }
+
+    fn reduce(&mut self) {
+        while self.explode() || self.split() {}
+    }
+}
+
+impl ops::Add for Number {
+    type Output = Number;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let mut sum = Number::pair(self, rhs);
+        sum.reduce();
+        sum
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Number::Regular(value) => write!(f, "{}", value),
+            Number::Pair(left, right) => write!(f, "[{},{}]", left, right),
+        }
+    }
+}
+
+fn parse_number(s: &str) -> Result<(Number, &str), ParseError> {
+    if let Some(rest) = s.strip_prefix('[') {
+        let (left, rest) = parse_number(rest)?;
+        let rest = rest
+            .strip_prefix(',')
+            .ok_or_else(|| ParseError::Other(format!("expected ',' at {:?}", rest)))?;
+        let (right, rest) = parse_number(rest)?;
+        let rest = rest
+            .strip_prefix(']')
+            .ok_or_else(|| ParseError::Other(format!("expected ']' at {:?}", rest)))?;
+        Ok((Number::pair(left, right), rest))
+    } else {
+        let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        Ok((Number::Regular(s[..end].parse()?), &s[end..]))
+    }
+}
+
+impl FromStr for Number {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match parse_number(s.trim())? {
+            (number, "") => Ok(number),
+            (_, rest) => Err(ParseError::Other(format!("unexpected {:?}", rest))),
+        }
+    }
+}
+
+fn sum(numbers: impl IntoIterator<Item = Number>) -> Option<Number> {
+    numbers.into_iter().reduce(|sum, number| sum + number)
+}
+
+fn part1<R: BufRead>(reader: R) -> crate::Result<String> {
+    let total = sum(parse_lines(reader)).unwrap();
+    Ok(total.magnitude().to_string())
+}
+
+fn part2<R: BufRead>(reader: R) -> crate::Result<String> {
+    let numbers: Vec<Number> = parse_lines(reader).collect();
+    let mut largest = 0;
+    for (i, a) in numbers.iter().enumerate() {
+        for (j, b) in numbers.iter().enumerate() {
+            if i != j {
+                largest = largest.max((a.clone() + b.clone()).magnitude());
+            }
+        }
+    }
+    Ok(largest.to_string())
+}
+
+pub fn build_runner() -> crate::Runner {
+    let mut runner = crate::Runner::default();
+    runner.add_fn("part1", || part1(open_file("data/day18_input.txt")?));
+    runner.add_fn("part2", || part2(open_file("data/day18_input.txt")?));
+    runner
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::read_file;
+
+    fn number(s: &str) -> Number {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_display() {
+        for s in [
+            "[1,2]",
+            "[[1,2],3]",
+            "[[[[1,3],[5,3]],[[1,3],[8,7]]],[[[4,9],[6,9]],[[8,2],[7,3]]]]",
+        ] {
+            assert_eq!(number(s).to_string(), s);
+        }
+        assert_eq!(
+            number("[12,3]"),
+            Number::pair(Number::Regular(12), Number::Regular(3))
+        );
+        assert!("[1,2".parse::<Number>().is_err());
+        assert!("[1,2]]".parse::<Number>().is_err());
+        assert!("[1;2]".parse::<Number>().is_err());
+    }
+
+    #[test]
+    fn test_explode() {
+        for (before, after) in [
+            ("[[[[[9,8],1],2],3],4]", "[[[[0,9],2],3],4]"),
+            ("[7,[6,[5,[4,[3,2]]]]]", "[7,[6,[5,[7,0]]]]"),
+            ("[[6,[5,[4,[3,2]]]],1]", "[[6,[5,[7,0]]],3]"),
+            (
+                "[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]",
+                "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+            ),
+            (
+                "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]",
+                "[[3,[2,[8,0]]],[9,[5,[7,0]]]]",
+            ),
+        ] {
+            let mut n = number(before);
+            assert!(n.explode());
+            assert_eq!(n.to_string(), after);
+        }
+    }
+
+    #[test]
+    fn test_split() {
+        for (before, after) in [("10", "[5,5]"), ("11", "[5,6]"), ("12", "[6,6]")] {
+            let mut n = number(before);
+            assert!(n.split());
+            assert_eq!(n.to_string(), after);
+        }
+        assert!(!number("[9,[1,2]]").split());
+    }
+
+    #[test]
+    fn test_add() {
+        // Each step of reducing the sum in the example
+        let mut n = Number::pair(number("[[[[4,3],4],4],[7,[[8,4],9]]]"), number("[1,1]"));
+        assert!(n.explode());
+        assert_eq!(n.to_string(), "[[[[0,7],4],[7,[[8,4],9]]],[1,1]]");
+        assert!(n.explode());
+        assert_eq!(n.to_string(), "[[[[0,7],4],[15,[0,13]]],[1,1]]");
+        assert!(!n.explode() && n.split());
+        assert_eq!(n.to_string(), "[[[[0,7],4],[[7,8],[0,13]]],[1,1]]");
+        assert!(!n.explode() && n.split());
+        assert_eq!(n.to_string(), "[[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]");
+        assert!(n.explode());
+        assert_eq!(n.to_string(), "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]");
+        assert!(!n.explode() && !n.split());
+
+        assert_eq!(
+            number("[[[[4,3],4],4],[7,[[8,4],9]]]") + number("[1,1]"),
+            number("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]")
+        );
+
+        let pairs = |n: u32| (1..=n).map(|i| number(&format!("[{},{}]", i, i)));
+        assert_eq!(
+            sum(pairs(4)).unwrap().to_string(),
+            "[[[[1,1],[2,2]],[3,3]],[4,4]]"
+        );
+        assert_eq!(
+            sum(pairs(5)).unwrap().to_string(),
+            "[[[[3,0],[5,3]],[4,4]],[5,5]]"
+        );
+        assert_eq!(
+            sum(pairs(6)).unwrap().to_string(),
+            "[[[[5,0],[7,4]],[5,5]],[6,6]]"
+        );
+        assert_eq!(
+            sum(parse_lines(read_file("data/day18_example2.txt")))
+                .unwrap()
+                .to_string(),
+            "[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]"
+        );
+    }
+
+    #[test]
+    fn test_magnitude() {
+        for (s, magnitude) in [
+            ("[[1,2],[[3,4],5]]", 143),
+            ("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]", 1384),
+            ("[[[[1,1],[2,2]],[3,3]],[4,4]]", 445),
+            ("[[[[3,0],[5,3]],[4,4]],[5,5]]", 791),
+            ("[[[[5,0],[7,4]],[5,5]],[6,6]]", 1137),
+            (
+                "[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]",
+                3488,
+            ),
+        ] {
+            assert_eq!(number(s).magnitude(), magnitude);
+        }
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(
+            sum(parse_lines(read_file("data/day18_example1.txt")))
+                .unwrap()
+                .to_string(),
+            "[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]"
+        );
+        assert_eq!(part1(read_file("data/day18_example1.txt")).unwrap(), "4140");
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(read_file("data/day18_example1.txt")).unwrap(), "3993");
+    }
+}
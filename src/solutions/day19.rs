@@ -0,0 +1,218 @@
+use std::collections::{HashSet, VecDeque};
+
+use super::prelude::*;
+use crate::error::ParseError;
+use crate::transform::{Rotation, Transform};
+use crate::util::{open_file, Counter};
+use crate::vector::Vector;
+
+type Vec3 = Vector<i64, 3>;
+
+/// The number of beacons two scanners must both detect to be sure they overlap.
+const MIN_SHARED: usize = 12;
+
+struct Scanner {
+    beacons: Vec<Vec3>,
+    /// The squared distances between every pair of beacons, which don't depend on the scanner's
+    /// orientation. Different pairs can be the same distance apart, so each distance is counted.
+    fingerprint: Counter<i64>,
+}
+
+impl Scanner {
+    fn new(beacons: Vec<Vec3>) -> Self {
+        let mut fingerprint = Counter::new();
+        for (i, a) in beacons.iter().enumerate() {
+            fingerprint.count(
+                beacons[i + 1..]
+                    .iter()
+                    .map(|b| (*a - b).squared_euclidean_length()),
+            );
+        }
+        Scanner {
+            beacons,
+            fingerprint,
+        }
+    }
+
+    /// Find the transform from `other`'s coordinates to this scanner's coordinates, if they
+    /// detect at least `MIN_SHARED` of the same beacons.
+    fn align(&self, other: &Scanner) -> Option<Transform<i64>> {
+        // Enough shared beacons means enough shared distances between them
+        let shared: usize = self
+            .fingerprint
+            .iter()
+            .map(|(distance, &n)| n.min(other.fingerprint.get(distance).copied().unwrap_or(0)))
+            .sum();
+        if shared < MIN_SHARED * (MIN_SHARED - 1) / 2 {
+            return None;
+        }
+        Rotation::all().find_map(|rotation| {
+            let rotated: Vec<Vec3> = other.beacons.iter().map(|b| rotation.apply(b)).collect();
+            let mut offsets: Counter<Vec3> = Counter::new();
+            for a in self.beacons.iter() {
+                offsets.count(rotated.iter().map(|b| *a - b));
+            }
+            let (&translation, n) = offsets.max_entry()?;
+            (n >= MIN_SHARED).then(|| Transform::new(rotation, translation))
+        })
+    }
+}
+
+fn parse_scanners<R: BufRead>(reader: R) -> crate::Result<Vec<Scanner>> {
+    let mut scanners = Vec::new();
+    let mut beacons = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.starts_with("---") {
+            if !beacons.is_empty() {
+                scanners.push(Scanner::new(std::mem::take(&mut beacons)));
+            }
+        } else if !line.trim().is_empty() {
+            beacons.push(line.parse::<Vec3>()?);
+        }
+    }
+    if beacons.is_empty() {
+        return Err(ParseError::Other("expected beacons after scanner header".into()).into());
+    }
+    scanners.push(Scanner::new(beacons));
+    Ok(scanners)
+}
+
+/// Find the transform from each scanner's coordinates to those of the first scanner, by
+/// aligning each newly placed scanner with every scanner not yet placed.
+fn place_scanners(scanners: &[Scanner]) -> crate::Result<Vec<Transform<i64>>> {
+    let mut placed: Vec<Option<Transform<i64>>> = vec![None; scanners.len()];
+    placed[0] = Some(Transform::identity());
+    let mut queue = VecDeque::from([0]);
+    while let Some(i) = queue.pop_front() {
+        let to_first = placed[i].unwrap();
+        for j in 0..scanners.len() {
+            if placed[j].is_some() {
+                continue;
+            }
+            if let Some(to_i) = scanners[i].align(&scanners[j]) {
+                placed[j] = Some(to_first.compose(&to_i));
+                queue.push_back(j);
+            }
+        }
+    }
+    placed
+        .into_iter()
+        .enumerate()
+        .map(|(i, t)| {
+            t.ok_or_else(|| crate::Error::Other(format!("scanner {} doesn't overlap any other", i)))
+        })
+        .collect()
+}
+
+fn part1<R: BufRead>(reader: R) -> crate::Result<String> {
+    let scanners = parse_scanners(reader)?;
+    let transforms = place_scanners(&scanners)?;
+    let beacons: HashSet<Vec3> = scanners
+        .iter()
+        .zip(transforms.iter())
+        .flat_map(|(scanner, transform)| transform.apply_all(&scanner.beacons))
+        .collect();
+    Ok(beacons.len().to_string())
+}
+
+fn part2<R: BufRead>(reader: R) -> crate::Result<String> {
+    let scanners = parse_scanners(reader)?;
+    let positions: Vec<Vec3> = place_scanners(&scanners)?
+        .into_iter()
+        .map(|transform| transform.translation)
+        .collect();
+    let mut largest = 0;
+    for (i, a) in positions.iter().enumerate() {
+        for b in positions[i + 1..].iter() {
+            largest = largest.max((*a - b).manhattan_length());
+        }
+    }
+    Ok(largest.to_string())
+}
+
+pub fn build_runner() -> crate::Runner {
+    let mut runner = crate::Runner::default();
+    runner.add_fn("part1", || part1(open_file("data/day19_input.txt")?));
+    runner.add_fn("part2", || part2(open_file("data/day19_input.txt")?));
+    runner
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{read_file, read_str};
+
+    fn random_beacon(seed: &mut u64) -> Vec3 {
+        let mut random = || {
+            *seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((*seed >> 33) % 2001) as i64 - 1000
+        };
+        Vec3::from([random(), random(), random()])
+    }
+
+    #[test]
+    fn test_align() {
+        let scanners = parse_scanners(read_file("data/day19_example1.txt")).unwrap();
+        assert_eq!(scanners.len(), 5);
+        assert_eq!(scanners[2].beacons.len(), 26);
+        assert!(scanners[0].align(&scanners[1]).is_some());
+        assert!(scanners[0].align(&scanners[2]).is_none());
+        let transforms = place_scanners(&scanners).unwrap();
+        assert_eq!(
+            transforms.iter().map(|t| t.translation).collect::<Vec<_>>(),
+            vec![
+                Vec3::from([0, 0, 0]),
+                Vec3::from([68, -1246, -43]),
+                Vec3::from([1105, -1205, 1229]),
+                Vec3::from([-92, -2380, -20]),
+                Vec3::from([-20, -1133, 1061]),
+            ]
+        );
+
+        // Without scanner 1, nothing connects the others to scanner 0
+        let input = std::fs::read_to_string("data/day19_example1.txt").unwrap();
+        let without_1: Vec<&str> = input
+            .split("\n\n")
+            .enumerate()
+            .filter_map(|(i, scanner)| (i != 1).then_some(scanner))
+            .collect();
+        let scanners = parse_scanners(read_str(&without_1.join("\n\n"))).unwrap();
+        assert_eq!(
+            place_scanners(&scanners).unwrap_err().to_string(),
+            "scanner 1 doesn't overlap any other"
+        );
+    }
+
+    #[test]
+    fn test_align_repeated_distances() {
+        // Completing a parallelogram from three of the shared beacons makes two pairs of them the
+        // same distance apart as two other pairs
+        let mut seed: u64 = 48;
+        let mut shared: Vec<Vec3> = (1..MIN_SHARED).map(|_| random_beacon(&mut seed)).collect();
+        shared.push(shared[0] + shared[2] - shared[1]);
+        let mut scanner = |beacons: Vec<Vec3>| {
+            beacons
+                .into_iter()
+                .chain((0..5).map(|_| random_beacon(&mut seed)))
+                .collect::<Vec<_>>()
+        };
+        let first = Scanner::new(scanner(shared.clone()));
+        let to_first = Transform::new(Rotation::all().nth(9).unwrap(), [400, -1100, 75].into());
+        let second = Scanner::new(scanner(to_first.inverse().apply_all(&shared).collect()));
+        assert!(first.fingerprint.len() < first.fingerprint.total());
+        assert_eq!(first.align(&second), Some(to_first));
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(read_file("data/day19_example1.txt")).unwrap(), "79");
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(read_file("data/day19_example1.txt")).unwrap(), "3621");
+    }
+}
@@ -0,0 +1,124 @@
+use super::prelude::*;
+use crate::grid::{Boundary, Grid, Point};
+use crate::util::open_file;
+
+struct Enhancer {
+    algorithm: Vec<bool>,
+    image: Grid<bool, 2>,
+}
+
+fn parse_pixels(line: &str) -> impl Iterator<Item = bool> + '_ {
+    line.bytes().map(|b| b == b'#')
+}
+
+impl Enhancer {
+    fn from_reader<R: BufRead>(reader: R) -> Enhancer {
+        let mut lines = reader.lines().map(|line| line.unwrap());
+        let algorithm: Vec<bool> = parse_pixels(&lines.next().unwrap()).collect();
+        assert_eq!(algorithm.len(), 512);
+        lines.next();
+        let rows: Vec<String> = lines.collect();
+        let image = Grid::new([rows[0].len() as i64, rows.len() as i64])
+            .with_boundary(Boundary::Default(false))
+            .with_data(rows.iter().flat_map(|row| parse_pixels(row)));
+        Enhancer { algorithm, image }
+    }
+
+    /// Look up the output pixel for the 3x3 square centred on `point`.
+    fn enhanced(&self, point: Point<2>) -> bool {
+        let mut index = 0;
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let lit = self.image.get(point + [dx, dy]).copied().unwrap();
+                index = (index << 1) | lit as usize;
+            }
+        }
+        self.algorithm[index]
+    }
+
+    /// Enhance the image once. Only pixels within one step of the image can differ from the
+    /// infinite background, which is itself enhanced from a square of nothing but background.
+    fn step(&mut self) {
+        let background = match self.image.boundary() {
+            Boundary::Default(lit) => *lit,
+            _ => unreachable!(),
+        };
+        let padded = self.image.pad(1, background);
+        let data: Vec<bool> = padded
+            .iter_points()
+            .map(|point| self.enhanced(point))
+            .collect();
+        let background = self.algorithm[if background { 511 } else { 0 }];
+        self.image = padded
+            .with_boundary(Boundary::Default(background))
+            .with_data(data);
+    }
+
+    /// Count the lit pixels, or `None` if infinitely many are lit.
+    fn count_lit(&self) -> Option<usize> {
+        match self.image.boundary() {
+            Boundary::Default(true) => None,
+            _ => Some(self.image.iter().filter(|(_, &lit)| lit).count()),
+        }
+    }
+}
+
+fn part1<R: BufRead>(reader: R) -> crate::Result<String> {
+    let mut enhancer = Enhancer::from_reader(reader);
+    for _ in 0..2 {
+        enhancer.step();
+    }
+    Ok(enhancer.count_lit().unwrap().to_string())
+}
+
+fn part2<R: BufRead>(reader: R) -> crate::Result<String> {
+    let mut enhancer = Enhancer::from_reader(reader);
+    for _ in 0..50 {
+        enhancer.step();
+    }
+    Ok(enhancer.count_lit().unwrap().to_string())
+}
+
+pub fn build_runner() -> crate::Runner {
+    let mut runner = crate::Runner::default();
+    runner.add_fn("part1", || part1(open_file("data/day20_input.txt")?));
+    runner.add_fn("part2", || part2(open_file("data/day20_input.txt")?));
+    runner
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::{read_file, read_str};
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(read_file("data/day20_example1.txt")).unwrap(), "35");
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(read_file("data/day20_example1.txt")).unwrap(), "3351");
+    }
+
+    #[test]
+    fn test_flashing_background() {
+        // All-dark squares become lit and all-lit squares go dark, so the background flips every
+        // step
+        let mut algorithm = vec!['.'; 512];
+        algorithm[0] = '#';
+        let input = format!(
+            "{}\n\n...\n.#.\n...\n",
+            algorithm.iter().collect::<String>()
+        );
+        let mut enhancer = Enhancer::from_reader(read_str(&input));
+        assert_eq!(enhancer.count_lit(), Some(1));
+        enhancer.step();
+        assert_eq!(enhancer.image.boundary(), &Boundary::Default(true));
+        assert_eq!(enhancer.count_lit(), None);
+        enhancer.step();
+        assert_eq!(enhancer.image.boundary(), &Boundary::Default(false));
+        assert_eq!(enhancer.image.size(), Point::from([7, 7]));
+        assert_eq!(enhancer.image.min_point(), Point::from([-2, -2]));
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use super::prelude::*;
+use crate::error::ParseError;
+use crate::util::open_file;
+
+/// The ways three rolls of a three-sided Dirac die can add up: each sum and the number of
+/// universes it happens in.
+const DIRAC_ROLLS: [(u8, u64); 7] = [(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)];
+
+/// A player's position on the board, from 1 to 10, and their score.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+struct Player {
+    position: u8,
+    score: u16,
+}
+
+impl Player {
+    fn new(position: u8) -> Self {
+        Player { position, score: 0 }
+    }
+
+    fn advance(self, steps: u32) -> Self {
+        let position = ((self.position as u32 - 1 + steps) % 10 + 1) as u8;
+        Player {
+            position,
+            score: self.score + position as u16,
+        }
+    }
+}
+
+fn parse_players<R: BufRead>(reader: R) -> crate::Result<[Player; 2]> {
+    let mut positions = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        let (_, position) = line.split_once(" starting position: ").ok_or_else(|| {
+            ParseError::Other(format!("expected starting position, got {:?}", line))
+        })?;
+        positions.push(Player::new(
+            position.trim().parse().map_err(ParseError::from)?,
+        ));
+    }
+    positions
+        .try_into()
+        .map_err(|_| ParseError::Other("expected two players".into()).into())
+}
+
+/// Play with the deterministic die until someone reaches 1000, returning the loser's score times
+/// the number of rolls.
+fn play_deterministic(mut players: [Player; 2]) -> u32 {
+    let mut rolls = 0;
+    for turn in (0..2).cycle() {
+        // The die rolls 1 to 100 in order, then starts again
+        let steps = (0..3).map(|i| (rolls + i) % 100 + 1).sum();
+        rolls += 3;
+        players[turn] = players[turn].advance(steps);
+        if players[turn].score >= 1000 {
+            return players[1 - turn].score as u32 * rolls;
+        }
+    }
+    unreachable!()
+}
+
+/// Count the universes in which each player wins with the Dirac die, where `players[0]` is the
+/// player about to move.
+///
+/// Only the positions and scores matter, not how they were reached, so results are memoized.
+fn count_wins(players: [Player; 2], memo: &mut HashMap<[Player; 2], [u64; 2]>) -> [u64; 2] {
+    if let Some(wins) = memo.get(&players).copied() {
+        return wins;
+    }
+    let mut wins = [0; 2];
+    for (steps, universes) in DIRAC_ROLLS {
+        let moved = players[0].advance(steps as u32);
+        if moved.score >= 21 {
+            wins[0] += universes;
+        } else {
+            // The other player moves next, so swap them round and their wins back
+            let [theirs, ours] = count_wins([players[1], moved], memo);
+            wins[0] += ours * universes;
+            wins[1] += theirs * universes;
+        }
+    }
+    memo.insert(players, wins);
+    wins
+}
+
+fn part1<R: BufRead>(reader: R) -> crate::Result<String> {
+    let players = parse_players(reader)?;
+    Ok(play_deterministic(players).to_string())
+}
+
+fn part2<R: BufRead>(reader: R) -> crate::Result<String> {
+    let players = parse_players(reader)?;
+    let wins = count_wins(players, &mut HashMap::new());
+    Ok(wins.iter().max().unwrap().to_string())
+}
+
+pub fn build_runner() -> crate::Runner {
+    let mut runner = crate::Runner::default();
+    runner.add_fn("part1", || part1(open_file("data/day21_input.txt")?));
+    runner.add_fn("part2", || part2(open_file("data/day21_input.txt")?));
+    runner
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+    use crate::util::read_str;
+
+    const EXAMPLE: &str = indoc! {"\
+        Player 1 starting position: 4
+        Player 2 starting position: 8
+    "};
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(read_str(EXAMPLE)).unwrap(), "739785");
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(read_str(EXAMPLE)).unwrap(), "444356092776315");
+        let wins = count_wins([Player::new(4), Player::new(8)], &mut HashMap::new());
+        assert_eq!(wins, [444356092776315, 341960390180808]);
+    }
+
+    #[test]
+    fn test_advance() {
+        let player = Player::new(7).advance(2 + 2 + 1);
+        assert_eq!(
+            player,
+            Player {
+                position: 2,
+                score: 2
+            }
+        );
+        assert_eq!(player.advance(18).position, 10);
+        assert!(parse_players(read_str("Player 1 starting position: 4\n")).is_err());
+    }
+}
@@ -14,6 +14,11 @@ mod day13;
 mod day14;
 mod day15;
 mod day16;
+mod day17;
+mod day18;
+mod day19;
+mod day20;
+mod day21;
 
 pub mod prelude;
 
@@ -45,4 +50,9 @@ register_solutions!(
     day14,
     day15,
     day16,
+    day17,
+    day18,
+    day19,
+    day20,
+    day21,
 );
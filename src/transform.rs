@@ -0,0 +1,168 @@
+use num::Signed;
+
+use crate::vector::{Coord, Vector};
+
+/// One of the 24 axis-aligned rotations of 3D space: component `i` of a rotated vector is
+/// component `axes[i]` of the original, negated if `negate[i]`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Rotation {
+    axes: [usize; 3],
+    negate: [bool; 3],
+}
+
+const PERMUTATIONS: [[usize; 3]; 6] = [
+    [0, 1, 2],
+    [0, 2, 1],
+    [1, 0, 2],
+    [1, 2, 0],
+    [2, 0, 1],
+    [2, 1, 0],
+];
+
+impl Rotation {
+    pub fn identity() -> Self {
+        Rotation {
+            axes: [0, 1, 2],
+            negate: [false; 3],
+        }
+    }
+
+    /// Iterate over all 24 rotations, starting with the identity.
+    pub fn all() -> impl Iterator<Item = Rotation> {
+        PERMUTATIONS.iter().flat_map(|&axes| {
+            // An odd permutation is a reflection, which an odd number of negations undoes
+            let odd = axes.iter().enumerate().filter(|&(i, &a)| i != a).count() == 2;
+            (0..8u8).filter_map(move |bits| {
+                let negate = [bits & 1 != 0, bits & 2 != 0, bits & 4 != 0];
+                let negations = negate.iter().filter(|&&n| n).count();
+                (odd == (negations % 2 == 1)).then_some(Rotation { axes, negate })
+            })
+        })
+    }
+
+    pub fn apply<C: Coord + Signed>(&self, v: &Vector<C, 3>) -> Vector<C, 3> {
+        let mut new = Vector::default();
+        for i in 0..3 {
+            new[i] = if self.negate[i] {
+                -v[self.axes[i]]
+            } else {
+                v[self.axes[i]]
+            };
+        }
+        new
+    }
+
+    /// Get the rotation equivalent to applying `first` and then this rotation.
+    pub fn compose(&self, first: &Rotation) -> Rotation {
+        let mut new = Rotation::identity();
+        for i in 0..3 {
+            new.axes[i] = first.axes[self.axes[i]];
+            new.negate[i] = self.negate[i] != first.negate[self.axes[i]];
+        }
+        new
+    }
+
+    pub fn inverse(&self) -> Rotation {
+        let mut new = Rotation::identity();
+        for i in 0..3 {
+            new.axes[self.axes[i]] = i;
+            new.negate[self.axes[i]] = self.negate[i];
+        }
+        new
+    }
+}
+
+/// A rotation followed by a translation.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Transform<C: Coord> {
+    pub rotation: Rotation,
+    pub translation: Vector<C, 3>,
+}
+
+impl<C: Coord + Signed> Transform<C> {
+    pub fn new(rotation: Rotation, translation: Vector<C, 3>) -> Self {
+        Transform {
+            rotation,
+            translation,
+        }
+    }
+
+    pub fn identity() -> Self {
+        Transform::new(Rotation::identity(), Vector::default())
+    }
+
+    pub fn apply(&self, v: &Vector<C, 3>) -> Vector<C, 3> {
+        self.rotation.apply(v) + self.translation
+    }
+
+    pub fn apply_all<'a, I>(&'a self, points: I) -> impl Iterator<Item = Vector<C, 3>> + 'a
+    where
+        I: IntoIterator<Item = &'a Vector<C, 3>>,
+        I::IntoIter: 'a,
+    {
+        points.into_iter().map(move |v| self.apply(v))
+    }
+
+    /// Get the transform equivalent to applying `first` and then this transform.
+    pub fn compose(&self, first: &Transform<C>) -> Transform<C> {
+        Transform::new(
+            self.rotation.compose(&first.rotation),
+            self.apply(&first.translation),
+        )
+    }
+
+    pub fn inverse(&self) -> Transform<C> {
+        let rotation = self.rotation.inverse();
+        Transform::new(rotation, -rotation.apply(&self.translation))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    type V3 = Vector<i64, 3>;
+
+    #[test]
+    fn test_rotations() {
+        let rotations: Vec<Rotation> = Rotation::all().collect();
+        assert_eq!(rotations.len(), 24);
+        assert_eq!(rotations[0], Rotation::identity());
+
+        let v = V3::from([1, 2, 3]);
+        let images: HashSet<V3> = rotations.iter().map(|r| r.apply(&v)).collect();
+        assert_eq!(images.len(), 24);
+        // Rotations preserve handedness, so never produce a mirror image
+        assert!(!images.contains(&V3::from([-1, 2, 3])));
+        assert!(!images.contains(&V3::from([2, 1, 3])));
+
+        let all: HashSet<Rotation> = rotations.iter().copied().collect();
+        for a in rotations.iter() {
+            assert_eq!(a.compose(&a.inverse()), Rotation::identity());
+            assert_eq!(a.inverse().apply(&a.apply(&v)), v);
+            for b in rotations.iter() {
+                let ab = a.compose(b);
+                assert!(all.contains(&ab));
+                assert_eq!(ab.apply(&v), a.apply(&b.apply(&v)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_transform() {
+        let rotation = Rotation::all().nth(5).unwrap();
+        let a = Transform::new(rotation, V3::from([10, -20, 30]));
+        let b = Transform::new(rotation.inverse(), V3::from([1, 2, 3]));
+        let v = V3::from([4, -5, 6]);
+
+        assert_eq!(a.inverse().apply(&a.apply(&v)), v);
+        assert_eq!(a.compose(&a.inverse()), Transform::identity());
+        assert_eq!(a.compose(&b).apply(&v), a.apply(&b.apply(&v)));
+
+        let points = vec![v, V3::default()];
+        let moved: Vec<V3> = a.apply_all(&points).collect();
+        assert_eq!(moved, vec![a.apply(&v), a.translation]);
+    }
+}
@@ -1,18 +1,30 @@
+use std::cmp;
 use std::collections::HashMap;
 use std::fmt;
 use std::fs::File;
 use std::hash::Hash;
 use std::io::{self, BufRead};
+use std::ops;
 use std::path::Path;
 use std::str::FromStr;
 
 use crate::grid::Grid;
 
+mod range_set;
+
+pub use range_set::{parse_range, RangeSet};
+
 pub fn read_file<P: AsRef<Path>>(path: P) -> impl BufRead {
     let file = File::open(path).unwrap();
     io::BufReader::new(file)
 }
 
+/// Open a file for reading, returning an error instead of panicking if it can't be opened, e.g. a
+/// puzzle input that hasn't been downloaded.
+pub fn open_file<P: AsRef<Path>>(path: P) -> crate::Result<impl BufRead> {
+    Ok(io::BufReader::new(File::open(path)?))
+}
+
 pub fn read_str(data: &str) -> impl BufRead + '_ {
     io::BufReader::new(data.as_bytes())
 }
@@ -51,33 +63,182 @@ where
     input.split(pattern).map(|x| x.parse::<T>().unwrap())
 }
 
-pub struct Counter<T: Clone + Eq + Hash>(HashMap<T, usize>);
+/// A count of how many times something has occurred, in a type that can be chosen large enough
+/// for the counts involved.
+pub trait Count: num::Unsigned + Copy + Ord + ops::AddAssign {}
+impl<T: num::Unsigned + Copy + Ord + ops::AddAssign> Count for T {}
+
+pub struct Counter<T: Clone + Eq + Hash, N: Count = usize>(HashMap<T, N>);
 
 impl<T: Clone + Eq + Hash> Counter<T> {
     pub fn new() -> Self {
         Counter(HashMap::new())
     }
+}
 
+impl<T: Clone + Eq + Hash, N: Count> Default for Counter<T, N> {
+    fn default() -> Self {
+        Counter(HashMap::new())
+    }
+}
+
+impl<T: Clone + Eq + Hash, N: Count> Counter<T, N> {
     pub fn count<I: IntoIterator<Item = T>>(&mut self, items: I) {
         for item in items {
-            *self.0.entry(item).or_default() += 1;
+            self.add(item, N::one());
         }
     }
+
+    /// Count `item` as having occurred `n` more times.
+    pub fn add(&mut self, item: T, n: N) {
+        *self.0.entry(item).or_insert_with(N::zero) += n;
+    }
+
+    /// Add all the counts of `other` to this counter.
+    pub fn merge(&mut self, other: &Self) {
+        for (item, &n) in other.0.iter() {
+            self.add(item.clone(), n);
+        }
+    }
+
+    /// Get the sum of all counts.
+    pub fn total(&self) -> N {
+        self.0.values().fold(N::zero(), |total, &n| total + n)
+    }
+
+    fn sorted_by<F>(&self, mut compare: F) -> Vec<(T, N)>
+    where
+        T: Ord,
+        F: FnMut(&N, &N) -> cmp::Ordering,
+    {
+        let mut entries: Vec<(T, N)> = self.0.iter().map(|(k, &v)| (k.clone(), v)).collect();
+        entries.sort_by(|(a, m), (b, n)| compare(m, n).then_with(|| a.cmp(b)));
+        entries
+    }
+
+    /// Get up to `n` items with the highest counts, highest first, with ties in item order.
+    pub fn most_common(&self, n: usize) -> Vec<(T, N)>
+    where
+        T: Ord,
+    {
+        let mut entries = self.sorted_by(|a, b| b.cmp(a));
+        entries.truncate(n);
+        entries
+    }
+
+    /// Get up to `n` items with the lowest counts, lowest first, with ties in item order.
+    pub fn least_common(&self, n: usize) -> Vec<(T, N)>
+    where
+        T: Ord,
+    {
+        let mut entries = self.sorted_by(N::cmp);
+        entries.truncate(n);
+        entries
+    }
+
+    /// Get an item with the highest count, if there are any items.
+    pub fn max_entry(&self) -> Option<(&T, N)> {
+        self.0.iter().map(|(k, &v)| (k, v)).max_by_key(|&(_, n)| n)
+    }
+
+    /// Get an item with the lowest count, if there are any items.
+    pub fn min_entry(&self) -> Option<(&T, N)> {
+        self.0.iter().map(|(k, &v)| (k, v)).min_by_key(|&(_, n)| n)
+    }
 }
 
-impl<T: Clone + Eq + Hash> std::ops::Deref for Counter<T> {
-    type Target = HashMap<T, usize>;
+impl<T: Clone + Eq + Hash, N: Count> std::ops::Deref for Counter<T, N> {
+    type Target = HashMap<T, N>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
     }
 }
 
-impl<T: Clone + Eq + Hash> IntoIterator for Counter<T> {
-    type Item = (T, usize);
-    type IntoIter = std::collections::hash_map::IntoIter<T, usize>;
+impl<T: Clone + Eq + Hash, N: Count> IntoIterator for Counter<T, N> {
+    type Item = (T, N);
+    type IntoIter = std::collections::hash_map::IntoIter<T, N>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.0.into_iter()
     }
 }
+
+impl<T: Clone + Eq + Hash, N: Count> FromIterator<T> for Counter<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter = Self::default();
+        counter.count(iter);
+        counter
+    }
+}
+
+impl<T: Clone + Eq + Hash, N: Count> Extend<T> for Counter<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.count(iter);
+    }
+}
+
+impl<T: Clone + Eq + Hash, N: Count> ops::Add for Counter<T, N> {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self::Output {
+        self.merge(&rhs);
+        self
+    }
+}
+
+/// Subtract counts, keeping only the items that still have a positive count.
+impl<T: Clone + Eq + Hash, N: Count> ops::Sub for Counter<T, N> {
+    type Output = Self;
+
+    fn sub(mut self, rhs: Self) -> Self::Output {
+        for (item, n) in rhs.0.into_iter() {
+            if let Some(count) = self.0.get_mut(&item) {
+                if *count > n {
+                    *count = *count - n;
+                } else {
+                    self.0.remove(&item);
+                }
+            }
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_file() {
+        assert!(open_file("data/day01_input.txt").is_ok());
+        assert!(matches!(
+            open_file("data/missing.txt"),
+            Err(crate::Error::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_counter() {
+        let counter: Counter<char> = "abracadabra".chars().collect();
+        assert_eq!(counter[&'a'], 5);
+        assert_eq!(counter.total(), 11);
+        assert_eq!(counter.most_common(3), vec![('a', 5), ('b', 2), ('r', 2)]);
+        assert_eq!(counter.most_common(1), vec![('a', 5)]);
+        assert_eq!(counter.max_entry(), Some((&'a', 5)));
+        assert_eq!(counter.least_common(2), vec![('c', 1), ('d', 1)]);
+        assert_eq!(counter.least_common(10).len(), 5);
+        assert_eq!(counter.min_entry().unwrap().1, 1);
+
+        let mut weighted: Counter<&str, u128> = Counter::default();
+        weighted.add("x", 1 << 100);
+        weighted.add("y", 3);
+        weighted.extend(["y", "z"]);
+        let other: Counter<&str, u128> = ["x", "y", "y", "y", "y"].into_iter().collect();
+        let sum = Counter::default() + weighted - other;
+        assert_eq!(sum.len(), 2);
+        assert_eq!(sum[&"x"], (1 << 100) - 1);
+        assert_eq!(sum[&"z"], 1);
+        assert!(!sum.contains_key(&"y"));
+    }
+}
@@ -0,0 +1,243 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::ParseError;
+use crate::vector::Coord;
+
+/// A set of integers, stored as sorted inclusive ranges that are merged whenever they overlap or
+/// touch.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RangeSet<C: Coord = i64> {
+    ranges: Vec<(C, C)>,
+}
+
+impl<C: Coord> Default for RangeSet<C> {
+    fn default() -> Self {
+        RangeSet { ranges: Vec::new() }
+    }
+}
+
+impl<C: Coord> RangeSet<C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Iterate over the disjoint ranges in the set, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (C, C)> + '_ {
+        self.ranges.iter().copied()
+    }
+
+    /// Get the number of integers in the set.
+    pub fn total_length(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|&(lo, hi)| (hi.to_i128().unwrap() - lo.to_i128().unwrap() + 1) as u128)
+            .sum()
+    }
+
+    pub fn contains(&self, value: C) -> bool {
+        let i = self.ranges.partition_point(|&(lo, _)| lo <= value);
+        i > 0 && self.ranges[i - 1].1 >= value
+    }
+
+    /// Add the integers from `lo` to `hi` inclusive.
+    pub fn insert(&mut self, mut lo: C, mut hi: C) {
+        if lo > hi {
+            return;
+        }
+        // Ranges that end before `lo` without touching it, and those that start at or before the
+        // integer after `hi`
+        let start = self
+            .ranges
+            .partition_point(|&(_, end)| end < lo && end + C::one() < lo);
+        let stop = self
+            .ranges
+            .partition_point(|&(begin, _)| begin <= hi || begin - C::one() <= hi);
+        if start < stop {
+            lo = lo.min(self.ranges[start].0);
+            hi = hi.max(self.ranges[stop - 1].1);
+        }
+        self.ranges.splice(start..stop, [(lo, hi)]);
+    }
+
+    /// Remove the integers from `lo` to `hi` inclusive.
+    pub fn remove(&mut self, lo: C, hi: C) {
+        if lo > hi {
+            return;
+        }
+        let start = self.ranges.partition_point(|&(_, end)| end < lo);
+        let stop = self.ranges.partition_point(|&(begin, _)| begin <= hi);
+        if start >= stop {
+            return;
+        }
+        let mut remaining = Vec::new();
+        if self.ranges[start].0 < lo {
+            remaining.push((self.ranges[start].0, lo - C::one()));
+        }
+        if self.ranges[stop - 1].1 > hi {
+            remaining.push((hi + C::one(), self.ranges[stop - 1].1));
+        }
+        self.ranges.splice(start..stop, remaining);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut new = self.clone();
+        for (lo, hi) in other.iter() {
+            new.insert(lo, hi);
+        }
+        new
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut a, mut b) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+        while let (Some(&&(a_lo, a_hi)), Some(&&(b_lo, b_hi))) = (a.peek(), b.peek()) {
+            let (lo, hi) = (a_lo.max(b_lo), a_hi.min(b_hi));
+            if lo <= hi {
+                ranges.push((lo, hi));
+            }
+            // Whichever range ends first can't overlap anything else
+            if a_hi < b_hi {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        RangeSet { ranges }
+    }
+
+    /// Get the integers from `lo` to `hi` inclusive that aren't in the set.
+    pub fn complement(&self, lo: C, hi: C) -> Self {
+        let mut new = Self::new();
+        new.insert(lo, hi);
+        for (lo, hi) in self.iter() {
+            new.remove(lo, hi);
+        }
+        new
+    }
+}
+
+impl<C: Coord> FromIterator<(C, C)> for RangeSet<C> {
+    fn from_iter<I: IntoIterator<Item = (C, C)>>(iter: I) -> Self {
+        let mut new = Self::new();
+        new.extend(iter);
+        new
+    }
+}
+
+impl<C: Coord> Extend<(C, C)> for RangeSet<C> {
+    fn extend<I: IntoIterator<Item = (C, C)>>(&mut self, iter: I) {
+        for (lo, hi) in iter {
+            self.insert(lo, hi);
+        }
+    }
+}
+
+/// Parse an inclusive range written as `a..b`, `a..=b` or `a-b` (either bound may be negative),
+/// or a single value `a`.
+pub fn parse_range<C: Coord + FromStr>(s: &str) -> Result<(C, C), ParseError>
+where
+    ParseError: From<C::Err>,
+{
+    let s = s.trim();
+    let (lo, hi) = if let Some((lo, hi)) = s.split_once("..") {
+        (lo, hi.strip_prefix('=').unwrap_or(hi))
+    } else {
+        // Skip the first character so a leading minus sign isn't taken as the separator
+        match s.char_indices().skip(1).find(|&(_, c)| c == '-') {
+            Some((i, _)) => (&s[..i], &s[i + 1..]),
+            None => (s, s),
+        }
+    };
+    let (lo, hi) = (lo.trim().parse()?, hi.trim().parse()?);
+    if lo > hi {
+        return Err(ParseError::Other(format!("empty range {:?}", s)));
+    }
+    Ok((lo, hi))
+}
+
+/// Parses comma-separated ranges in any form accepted by `parse_range`.
+impl<C: Coord + FromStr> FromStr for RangeSet<C>
+where
+    ParseError: From<C::Err>,
+{
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .filter(|part| !part.trim().is_empty())
+            .map(parse_range)
+            .collect()
+    }
+}
+
+/// Formats as comma-separated `a..b` ranges, which `from_str` parses back.
+impl<C: Coord + fmt::Display> fmt::Display for RangeSet<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (lo, hi)) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{}..{}", lo, hi)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_set() {
+        let mut set: RangeSet = RangeSet::new();
+        set.insert(1, 3);
+        set.insert(6, 8);
+        set.insert(4, 4);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![(1, 4), (6, 8)]);
+        set.insert(5, 5);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![(1, 8)]);
+        set.insert(-5, -3);
+        set.insert(20, 10);
+        assert_eq!(set.total_length(), 11);
+        assert!(set.contains(-4));
+        assert!(!set.contains(0));
+        assert!(!set.contains(9));
+
+        set.remove(3, 5);
+        assert_eq!(set.to_string(), "-5..-3,1..2,6..8");
+        set.remove(-10, 1);
+        assert_eq!(set.to_string(), "2..2,6..8");
+        set.remove(0, 100);
+        assert!(set.is_empty());
+
+        let a: RangeSet = [(0, 10), (20, 30)].into_iter().collect();
+        let b: RangeSet = [(5, 25), (28, 28), (40, 50)].into_iter().collect();
+        assert_eq!(a.intersection(&b).to_string(), "5..10,20..25,28..28");
+        assert_eq!(a.union(&b).to_string(), "0..30,40..50");
+        assert_eq!(a.complement(-5, 35).to_string(), "-5..-1,11..19,31..35");
+        assert_eq!(a.complement(2, 8), RangeSet::new());
+    }
+
+    #[test]
+    fn test_range_set_parse() {
+        assert_eq!(parse_range::<i64>("20..30").unwrap(), (20, 30));
+        assert_eq!(parse_range::<i64>("-10..=-5").unwrap(), (-10, -5));
+        assert_eq!(parse_range::<i64>("3-7").unwrap(), (3, 7));
+        assert_eq!(parse_range::<i64>("-7--3").unwrap(), (-7, -3));
+        assert_eq!(parse_range::<i64>(" 4 ").unwrap(), (4, 4));
+        assert!(parse_range::<i64>("5..1").is_err());
+        assert!(parse_range::<i64>("a-b").is_err());
+
+        let set: RangeSet = "1-3, 2..5,10..10".parse().unwrap();
+        assert_eq!(set.to_string(), "1..5,10..10");
+        assert_eq!(set.to_string().parse::<RangeSet>().unwrap(), set);
+    }
+}
@@ -1,10 +1,13 @@
 use std::cmp;
 use std::fmt;
+use std::iter;
 use std::ops;
 use std::str::FromStr;
 
 use num::Signed;
 
+use crate::error::ParseError;
+
 pub trait Coord: num::Integer + num::CheckedSub + num::ToPrimitive + Copy {}
 impl<T: num::Integer + num::CheckedSub + num::ToPrimitive + Copy> Coord for T {}
 
@@ -63,42 +66,110 @@ impl<C: Coord, const N: usize> ops::IndexMut<usize> for Vector<C, N> {
     }
 }
 
-impl<T: AsRef<[C]>, C: Coord, const N: usize> ops::Add<T> for Vector<C, N> {
-    type Output = Self;
+/// Implement a component-wise operator (and its assigning form) for a vector with any of the
+/// right-hand sides a vector can reasonably be combined with.
+macro_rules! impl_component_op {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $op:tt) => {
+        impl_component_op!(@rhs Vector<C, N>, $trait, $method, $assign_trait, $assign_method, $op);
+        impl_component_op!(@rhs &Vector<C, N>, $trait, $method, $assign_trait, $assign_method, $op);
+        impl_component_op!(@rhs [C; N], $trait, $method, $assign_trait, $assign_method, $op);
+        impl_component_op!(@rhs &[C; N], $trait, $method, $assign_trait, $assign_method, $op);
+    };
+    (@rhs $rhs:ty, $trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $op:tt) => {
+        impl<C: Coord, const N: usize> ops::$assign_trait<$rhs> for Vector<C, N> {
+            fn $assign_method(&mut self, rhs: $rhs) {
+                for i in 0..N {
+                    self[i] = self[i] $op rhs[i];
+                }
+            }
+        }
 
-    fn add(mut self, rhs: T) -> Self::Output {
-        let rhs = rhs.as_ref();
-        for i in 0..N {
-            self[i] = self[i] + rhs[i];
+        impl<C: Coord, const N: usize> ops::$trait<$rhs> for Vector<C, N> {
+            type Output = Self;
+
+            fn $method(mut self, rhs: $rhs) -> Self::Output {
+                ops::$assign_trait::$assign_method(&mut self, rhs);
+                self
+            }
+        }
+    };
+}
+
+impl_component_op!(Add, add, AddAssign, add_assign, +);
+impl_component_op!(Sub, sub, SubAssign, sub_assign, -);
+impl_component_op!(Mul, mul, MulAssign, mul_assign, *);
+
+impl<C: Coord, const N: usize> ops::MulAssign<C> for Vector<C, N> {
+    fn mul_assign(&mut self, rhs: C) {
+        for v in self.0.iter_mut() {
+            *v = *v * rhs;
         }
-        self
     }
 }
 
-impl<T: AsRef<[C]>, C: Coord, const N: usize> ops::Sub<T> for Vector<C, N> {
+impl<C: Coord, const N: usize> ops::Mul<C> for Vector<C, N> {
     type Output = Self;
 
-    fn sub(mut self, rhs: T) -> Self::Output {
-        let rhs = rhs.as_ref();
-        for i in 0..N {
-            self[i] = self[i] - rhs[i];
+    fn mul(mut self, rhs: C) -> Self::Output {
+        self *= rhs;
+        self
+    }
+}
+
+/// Integer division of each component, rounding towards zero like the underlying type.
+impl<C: Coord, const N: usize> ops::DivAssign<C> for Vector<C, N> {
+    fn div_assign(&mut self, rhs: C) {
+        for v in self.0.iter_mut() {
+            *v = *v / rhs;
         }
+    }
+}
+
+impl<C: Coord, const N: usize> ops::Div<C> for Vector<C, N> {
+    type Output = Self;
+
+    fn div(mut self, rhs: C) -> Self::Output {
+        self /= rhs;
         self
     }
 }
 
-impl<T: AsRef<[C]>, C: Coord, const N: usize> ops::Mul<T> for Vector<C, N> {
+impl<C: Coord + Signed, const N: usize> ops::Neg for Vector<C, N> {
     type Output = Self;
 
-    fn mul(mut self, rhs: T) -> Self::Output {
-        let rhs = rhs.as_ref();
-        for i in 0..N {
-            self[i] = self[i] * rhs[i];
+    fn neg(mut self) -> Self::Output {
+        for v in self.0.iter_mut() {
+            *v = -*v;
         }
         self
     }
 }
 
+impl<C: Coord, const N: usize> iter::Sum for Vector<C, N> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |acc, v| acc + v)
+    }
+}
+
+impl<'a, C: Coord, const N: usize> iter::Sum<&'a Vector<C, N>> for Vector<C, N> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |acc, v| acc + v)
+    }
+}
+
+/// The component-wise product.
+impl<C: Coord, const N: usize> iter::Product for Vector<C, N> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Vector([C::one(); N]), |acc, v| acc * v)
+    }
+}
+
+impl<'a, C: Coord, const N: usize> iter::Product<&'a Vector<C, N>> for Vector<C, N> {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Vector([C::one(); N]), |acc, v| acc * v)
+    }
+}
+
 impl<C: Coord, const N: usize> num::CheckedSub for Vector<C, N> {
     fn checked_sub(&self, rhs: &Self) -> Option<Self> {
         let mut new = self.clone();
@@ -125,6 +196,36 @@ impl<C: Coord, const N: usize> Vector<C, N> {
         }
         new
     }
+
+    /// Convert to another coordinate type, or `None` if any component doesn't fit in it.
+    pub fn try_cast<D: Coord + num::NumCast>(&self) -> Option<Vector<D, N>> {
+        let mut new = Vector::default();
+        for i in 0..N {
+            new[i] = D::from(self[i])?;
+        }
+        Some(new)
+    }
+
+    /// Convert to another coordinate type, e.g. to use any vector as a `grid::Point`.
+    ///
+    /// Panics if any component doesn't fit in the new type.
+    pub fn cast<D: Coord + num::NumCast>(&self) -> Vector<D, N> {
+        self.try_cast()
+            .expect("vector component out of range for coordinate type")
+    }
+
+    pub fn dot(&self, rhs: &Self) -> C {
+        let mut sum = C::zero();
+        for i in 0..N {
+            sum = sum + self[i] * rhs[i];
+        }
+        sum
+    }
+
+    /// Get the square of the Euclidean length, which stays exact in integer coordinates.
+    pub fn squared_euclidean_length(&self) -> C {
+        self.dot(self)
+    }
 }
 
 // impl<C: Coord + Unsigned, const N: usize> Vector<C, N> {
@@ -145,20 +246,95 @@ impl<C: Coord + Signed, const N: usize> Vector<C, N> {
         }
         sum
     }
-}
 
-impl<C: Coord + FromStr, const N: usize> FromStr for Vector<C, N> {
-    type Err = C::Err;
+    /// Get the largest absolute value of any component, i.e. the number of king's moves needed to
+    /// reach this vector from the origin.
+    pub fn chebyshev_length(&self) -> C {
+        let mut max = C::zero();
+        for v in self.0 {
+            max = cmp::max(max, v.abs());
+        }
+        max
+    }
+
+    pub fn abs(&self) -> Self {
+        Vector(self.0.map(|v| v.abs()))
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    /// Get the sign of each component, which for a direction along an axis or diagonal gives the
+    /// unit step in that direction.
+    pub fn signum(&self) -> Self {
+        Vector(self.0.map(|v| v.signum()))
+    }
+}
+
+impl<C: Coord + FromStr, const N: usize> Vector<C, N>
+where
+    ParseError: From<C::Err>,
+{
+    /// Parse components separated by characters matching `separator`. Surrounding whitespace, a
+    /// single pair of enclosing brackets (`<>`, `()` or `[]`) and `name=` prefixes on each
+    /// component are ignored, so `<1, 2, 3>` and `x=1,y=2` are both accepted. If the separator
+    /// matches whitespace then runs of it count as one, so aligned columns are accepted too.
+    pub fn parse_separated<F: Fn(char) -> bool>(s: &str, separator: F) -> Result<Self, ParseError> {
+        let s = s.trim();
+        let s = [('<', '>'), ('(', ')'), ('[', ']')]
+            .iter()
+            .find_map(|&(open, close)| s.strip_prefix(open)?.strip_suffix(close))
+            .unwrap_or(s);
+        let whitespace = separator(' ');
+        let parts: Vec<&str> = s
+            .split(&separator)
+            .filter(|part| !(whitespace && part.is_empty()))
+            .collect();
+        if parts.len() != N {
+            return Err(ParseError::WrongDimension {
+                expected: N,
+                actual: parts.len(),
+            });
+        }
         let mut new = Self::default();
-        for (i, part) in s.split(',').enumerate() {
-            new[i] = part.parse()?;
+        for (i, part) in parts.into_iter().enumerate() {
+            let part = part.trim();
+            let value = match part.split_once('=') {
+                Some((name, value)) if is_identifier(name.trim()) => value,
+                _ => part,
+            };
+            new[i] = value.trim().parse()?;
         }
         Ok(new)
     }
 }
 
+fn is_identifier(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && s.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+impl<C: Coord + FromStr, const N: usize> FromStr for Vector<C, N>
+where
+    ParseError: From<C::Err>,
+{
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_separated(s, |c| c == ',')
+    }
+}
+
+/// Formats as comma-separated components, which `from_str` parses back.
+impl<C: Coord + fmt::Display, const N: usize> fmt::Display for Vector<C, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, v) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{}", v)?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -174,4 +350,116 @@ mod tests {
         assert_eq!(Vec::from([1, 2, 3]) + [4, 5, 6], Vec::from([5, 7, 9]));
         assert_eq!(Vec::from([1, 2, 3]) * [2, 3, 4], Vec::from([2, 6, 12]));
     }
+
+    #[test]
+    #[allow(clippy::op_ref)] // Exercises the impls that take the right-hand side by reference
+    fn test_vector_arithmetic() {
+        type Vec = Vector<i64, 3>;
+        let a = Vec::from([1, -2, 3]);
+        let b = Vec::from([4, 5, -6]);
+        assert_eq!(a + &b, Vec::from([5, 3, -3]));
+        assert_eq!(a - &[1, 1, 1], Vec::from([0, -3, 2]));
+        assert_eq!(a * 3, Vec::from([3, -6, 9]));
+        assert_eq!(Vec::from([7, -7, 6]) / 2, Vec::from([3, -3, 3]));
+        assert_eq!(-a, Vec::from([-1, 2, -3]));
+
+        let mut c = a;
+        c += b;
+        c -= [1, 1, 1];
+        c *= 2;
+        assert_eq!(c, Vec::from([8, 4, -8]));
+        c *= &Vec::from([1, 0, -1]);
+        assert_eq!(c, Vec::from([8, 0, 8]));
+
+        assert_eq!(a.dot(&b), 4 - 10 - 18);
+        assert_eq!(a.manhattan_length(), 6);
+        assert_eq!(a.chebyshev_length(), 3);
+        assert_eq!(a.squared_euclidean_length(), 14);
+        assert_eq!(a.abs(), Vec::from([1, 2, 3]));
+        assert_eq!(Vec::from([5, 0, -2]).signum(), Vec::from([1, 0, -1]));
+
+        assert_eq!([a, b].into_iter().sum::<Vec>(), a + b);
+        assert_eq!([a, b].iter().sum::<Vec>(), a + b);
+        assert_eq!(Vec::default(), std::iter::empty::<Vec>().sum());
+        assert_eq!([a, b].iter().product::<Vec>(), a * b);
+    }
+
+    #[test]
+    fn test_vector_parse() {
+        type Vec = Vector<i64, 3>;
+        assert_eq!("1,2,3".parse::<Vec>().unwrap(), Vec::from([1, 2, 3]));
+        assert_eq!(
+            " <1, -2, 3> ".parse::<Vec>().unwrap(),
+            Vec::from([1, -2, 3])
+        );
+        assert_eq!("(1,2,3)".parse::<Vec>().unwrap(), Vec::from([1, 2, 3]));
+        assert_eq!(
+            "x=1, y=2, z=3".parse::<Vec>().unwrap(),
+            Vec::from([1, 2, 3])
+        );
+        assert_eq!(
+            Vec::parse_separated("1 2  3", char::is_whitespace).unwrap(),
+            Vec::from([1, 2, 3])
+        );
+        assert_eq!(
+            Vec::parse_separated("  -1\t 20   300 ", char::is_whitespace).unwrap(),
+            Vec::from([-1, 20, 300])
+        );
+        assert!(matches!(
+            "1,,3".parse::<Vec>(),
+            Err(ParseError::ParseIntError(_))
+        ));
+        assert_eq!(
+            Vec::parse_separated("[1 2 3]", |c| c == ' ').unwrap(),
+            Vec::from([1, 2, 3])
+        );
+
+        assert!(matches!(
+            "1,2".parse::<Vec>(),
+            Err(ParseError::WrongDimension {
+                expected: 3,
+                actual: 2
+            })
+        ));
+        assert!(matches!(
+            "1,2,3,4".parse::<Vec>(),
+            Err(ParseError::WrongDimension {
+                expected: 3,
+                actual: 4
+            })
+        ));
+        assert!(matches!(
+            "1,x,3".parse::<Vec>(),
+            Err(ParseError::ParseIntError(_))
+        ));
+        assert!(matches!(
+            "1=2,2,3".parse::<Vec>(),
+            Err(ParseError::ParseIntError(_))
+        ));
+        assert_eq!(
+            "x_1 = 1, y=2, z2=3".parse::<Vec>().unwrap(),
+            Vec::from([1, 2, 3])
+        );
+
+        let v = Vec::from([-4, 0, 12]);
+        assert_eq!(v.to_string(), "-4,0,12");
+        assert_eq!(v.to_string().parse::<Vec>().unwrap(), v);
+    }
+
+    #[test]
+    fn test_vector_cast() {
+        let v: Vector<i16, 2> = Vector::from([3, -4]);
+        let point: crate::grid::Point<2> = v.cast();
+        assert_eq!(point, Vector::from([3i64, -4]));
+        assert_eq!(v.try_cast::<u8>(), None);
+        assert_eq!(v.abs().try_cast::<u8>(), Some(Vector::from([3u8, 4])));
+        assert_eq!(Vector::from([300i64, 0]).try_cast::<u8>(), None);
+        assert_eq!(Vector::from([usize::MAX, 0]).try_cast::<i64>(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_vector_cast_overflow() {
+        Vector::from([-1i64, 0]).cast::<usize>();
+    }
 }